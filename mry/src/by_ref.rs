@@ -0,0 +1,171 @@
+use std::{
+    fmt,
+    ptr::NonNull,
+    sync::Arc,
+    thread::{self, ThreadId},
+};
+
+use parking_lot::Mutex;
+
+use crate::ArgMatcher;
+
+/// An argument matched by reference while the mocked function is called, enabled by
+/// `#[mry::mry(by_ref(File))]`. Only its projection is recorded, so the argument needs neither
+/// `Clone` nor `Send`, but must be `Sync` and `'static`.
+pub struct ByRef<T: ?Sized, P = ()> {
+    target: Arc<Target<T>>,
+    projection: P,
+}
+
+impl<T: ?Sized, P> ByRef<T, P> {
+    /// The value recorded for the argument, like `file.path().to_owned()`
+    pub fn projection(&self) -> &P {
+        &self.projection
+    }
+
+    /// Matches the projection instead of the argument. Unlike a closure taking the argument
+    /// by reference, this can match recorded calls with `assert_called`.
+    pub fn projected(matcher: impl Into<ArgMatcher<P>>) -> ArgMatcher<Self>
+    where
+        P: Send + 'static,
+        T: 'static,
+    {
+        let matcher = matcher.into();
+        ArgMatcher::Fn(Box::new(move |input: &Self| {
+            matcher.matches(&input.projection)
+        }))
+    }
+
+    /// Calls `f` with the argument if its call is still in progress on this thread
+    fn with_target<R>(&self, f: impl FnOnce(&T) -> R) -> Option<R> {
+        if thread::current().id() != self.target.thread {
+            return None;
+        }
+        let ptr = (*self.target.ptr.lock())?;
+        // SAFETY: the pointer is released by `Borrows` before the call returns, and the call
+        // cannot return while this thread is here.
+        Some(f(unsafe { ptr.as_ref() }))
+    }
+}
+
+impl<T: ?Sized, P: Clone> Clone for ByRef<T, P> {
+    fn clone(&self) -> Self {
+        Self {
+            target: self.target.clone(),
+            projection: self.projection.clone(),
+        }
+    }
+}
+
+impl<T: ?Sized, P: fmt::Debug> fmt::Debug for ByRef<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ByRef").field(&self.projection).finish()
+    }
+}
+
+impl<T, P, F> From<F> for ArgMatcher<ByRef<T, P>>
+where
+    T: ?Sized + 'static,
+    F: Fn(&T) -> bool + Send + 'static,
+{
+    fn from(matcher: F) -> Self {
+        ArgMatcher::Fn(Box::new(move |input: &ByRef<T, P>| {
+            input.with_target(&matcher).unwrap_or_else(|| {
+                panic!(
+                    "`{}` is matched by reference, which is possible only while the mocked function is called. Use `mry::ByRef::projected` to match recorded calls",
+                    std::any::type_name::<T>()
+                )
+            })
+        }))
+    }
+}
+
+/// The argument lent to `ByRef`, and the thread calling the mocked function
+struct Target<T: ?Sized> {
+    ptr: Mutex<Option<NonNull<T>>>,
+    thread: ThreadId,
+}
+
+// SAFETY: the pointer is a shared reference to `T: Sync`, dereferenced only while it is lent.
+unsafe impl<T: ?Sized + Sync> Send for Target<T> {}
+unsafe impl<T: ?Sized + Sync> Sync for Target<T> {}
+
+trait Release: Send + Sync {
+    fn release(&self);
+}
+
+impl<T: ?Sized + Sync> Release for Target<T> {
+    fn release(&self) {
+        *self.ptr.lock() = None;
+    }
+}
+
+/// Lends the arguments of a call to `ByRef` until it is dropped at the end of the mock lookup
+#[doc(hidden)]
+#[derive(Default)]
+pub struct Borrows(Vec<Arc<dyn Release>>);
+
+impl Borrows {
+    /// `target` is released when this is dropped, which happens before the call returns.
+    /// `T: 'static` keeps matchers from borrowing anything from the argument beyond the call.
+    pub fn lend<T: ?Sized + Sync + 'static, P>(
+        &mut self,
+        target: &T,
+        projection: P,
+    ) -> ByRef<T, P> {
+        let target = Arc::new(Target {
+            ptr: Mutex::new(Some(NonNull::from(target))),
+            thread: thread::current().id(),
+        });
+        self.0.push(target.clone());
+        ByRef { target, projection }
+    }
+}
+
+impl Drop for Borrows {
+    fn drop(&mut self) {
+        for target in &self.0 {
+            target.release();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct NotClone(u8);
+
+    #[test]
+    fn matches_while_lent() {
+        let arg = NotClone(1);
+        let mut borrows = Borrows::default();
+        let by_ref = borrows.lend(&arg, ());
+        let matcher: ArgMatcher<ByRef<NotClone>> = (|arg: &NotClone| arg.0 == 1).into();
+
+        assert!(matcher.matches(&by_ref));
+    }
+
+    #[test]
+    #[should_panic(expected = "is matched by reference")]
+    fn released_on_drop() {
+        let arg = NotClone(1);
+        let mut borrows = Borrows::default();
+        let by_ref = borrows.lend(&arg, ());
+        drop(borrows);
+        let matcher: ArgMatcher<ByRef<NotClone>> = (|arg: &NotClone| arg.0 == 1).into();
+
+        matcher.matches(&by_ref);
+    }
+
+    #[test]
+    fn projected() {
+        let arg = NotClone(1);
+        let mut borrows = Borrows::default();
+        let by_ref = borrows.lend(&arg, arg.0);
+        drop(borrows);
+
+        assert!(ByRef::<NotClone, u8>::projected(1).matches(&by_ref));
+        assert!(!ByRef::<NotClone, u8>::projected(2).matches(&by_ref));
+    }
+}
@@ -0,0 +1,204 @@
+use std::{
+    cell::RefCell,
+    fmt::{self, Debug},
+    panic::{self, AssertUnwindSafe},
+    sync::Arc,
+};
+
+use parking_lot::Mutex;
+
+/// The number of choices explored by `explore`. Later choices take the first alternative.
+pub const DEFAULT_EXPLORE_DEPTH: usize = 8;
+
+// The run of `explore` on this thread, captured by `returns_one_of` when the rule is made.
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<Mutex<Run>>>> = const { RefCell::new(None) };
+}
+
+pub(crate) fn current() -> Option<Arc<Mutex<Run>>> {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+/// A run of the closure given to `explore`, which replays `prefix` and takes the first
+/// alternative after that
+pub(crate) struct Run {
+    prefix: Vec<usize>,
+    depth: usize,
+    choices: Vec<Choice>,
+}
+
+struct Choice {
+    name: &'static str,
+    index: usize,
+    count: usize,
+    explored: bool,
+    value: String,
+}
+
+impl Run {
+    /// Chooses one of the alternatives for a call of `name`
+    pub(crate) fn choose(&mut self, name: &'static str, alternatives: &[impl Debug]) -> usize {
+        let position = self.choices.len();
+        let explored = position < self.depth;
+        let index = self.prefix.get(position).copied().unwrap_or(0);
+        if index >= alternatives.len() {
+            panic!(
+                "{name} has {} alternatives at choice #{position}, but the previous run of `mry::explore` chose #{index} there. The closure must make the same choices for the same outputs\n{}",
+                alternatives.len(),
+                Path(&self.choices)
+            );
+        }
+        self.choices.push(Choice {
+            name,
+            index,
+            count: alternatives.len(),
+            explored,
+            value: format!("{:?}", alternatives[index]),
+        });
+        index
+    }
+
+    /// The prefix of the next run, or `None` if all combinations are explored
+    fn next(&self) -> Option<Vec<usize>> {
+        let last = self
+            .choices
+            .iter()
+            .rposition(|choice| choice.explored && choice.index + 1 < choice.count)?;
+        let mut prefix: Vec<_> = self.choices[..last].iter().map(|c| c.index).collect();
+        prefix.push(self.choices[last].index + 1);
+        Some(prefix)
+    }
+}
+
+struct Path<'a>(&'a [Choice]);
+
+impl fmt::Display for Path<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "choices:")?;
+        if self.0.is_empty() {
+            write!(f, " none")?;
+        }
+        for choice in self.0 {
+            write!(
+                f,
+                "\n  {} returned #{} of {}: {}",
+                choice.name, choice.index, choice.count, choice.value
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Restores the run of the thread even if the closure panics
+struct Restore(Option<Arc<Mutex<Run>>>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        CURRENT.with(|current| *current.borrow_mut() = self.0.take());
+    }
+}
+
+/// Runs the closure once for every combination of the alternatives returned by
+/// `returns_one_of` rules made in it, up to [`DEFAULT_EXPLORE_DEPTH`] choices per run.
+/// Returns the number of runs. If a run panics, the panic is raised again with its choices.
+#[track_caller]
+pub fn explore(f: impl FnMut()) -> usize {
+    explore_to_depth(DEFAULT_EXPLORE_DEPTH, f)
+}
+
+/// Same as [`explore`], but explores up to `depth` choices per run
+#[track_caller]
+pub fn explore_to_depth(depth: usize, mut f: impl FnMut()) -> usize {
+    if current().is_some() {
+        panic!("`mry::explore` cannot be nested");
+    }
+    let (mut prefix, mut runs) = (Vec::new(), 0);
+    loop {
+        runs += 1;
+        let run = Arc::new(Mutex::new(Run {
+            prefix,
+            depth,
+            choices: Vec::new(),
+        }));
+        let restore = Restore(CURRENT.with(|current| current.replace(Some(run.clone()))));
+        let result = panic::catch_unwind(AssertUnwindSafe(&mut f));
+        drop(restore);
+        let run = run.lock();
+        if let Err(payload) = result {
+            let message = payload
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("Box<dyn Any>");
+            panic!(
+                "{message}\n`mry::explore` failed in run {runs} with the {}",
+                Path(&run.choices)
+            );
+        }
+        match run.next() {
+            Some(next) => prefix = next,
+            None => return runs,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn choose(alternatives: &[u8]) -> u8 {
+        let run = current().unwrap();
+        let index = run.lock().choose("choose", alternatives);
+        alternatives[index]
+    }
+
+    #[test]
+    fn explores_all_combinations() {
+        let mut seen = Vec::new();
+        let runs = explore(|| seen.push((choose(&[1, 2, 3]), choose(&[4, 5]))));
+
+        assert_eq!(runs, 6);
+        assert_eq!(seen, [(1, 4), (1, 5), (2, 4), (2, 5), (3, 4), (3, 5)]);
+    }
+
+    #[test]
+    fn choices_depend_on_previous_ones() {
+        let mut seen = Vec::new();
+        explore(|| {
+            if choose(&[0, 1]) == 1 {
+                seen.push(vec![1, choose(&[2, 3])]);
+            } else {
+                seen.push(vec![0]);
+            }
+        });
+
+        assert_eq!(seen, [vec![0], vec![1, 2], vec![1, 3]]);
+    }
+
+    #[test]
+    fn bounded_by_depth() {
+        let mut seen = Vec::new();
+        explore_to_depth(1, || seen.push((choose(&[1, 2]), choose(&[3, 4]))));
+
+        assert_eq!(seen, [(1, 3), (2, 3)]);
+    }
+
+    #[test]
+    fn restores_after_panic() {
+        let result = panic::catch_unwind(|| explore(|| assert_ne!(choose(&[1, 2]), 2)));
+
+        assert!(result.is_err());
+        assert!(current().is_none());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "failed: sum\n  left: 5\n right: 5\n`mry::explore` failed in run 2 with the choices:\n  choose returned #1 of 2: 2\n  choose returned #0 of 1: 3"
+    )]
+    fn panics_with_choices() {
+        explore(|| {
+            let first = choose(&[1, 2]);
+            assert_ne!(first + choose(&[3]), 5, "sum");
+        });
+    }
+}
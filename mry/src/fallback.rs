@@ -0,0 +1,115 @@
+use std::marker::PhantomData;
+
+/// What a mock does when no rule matches a call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Fallback {
+    /// Panics with "mock not found"
+    #[default]
+    Panic,
+    /// Calls the real implementation
+    CallsRealImpl,
+    /// Returns `Default::default()`, or panics if the output doesn't implement `Default`
+    Default,
+}
+
+/// Fallback of a mocked function given by `#[mry::mry]`
+#[doc(hidden)]
+pub struct TypeFallback<O> {
+    pub policy: Option<Fallback>,
+    pub default: Option<fn() -> O>,
+}
+
+impl<O> Default for TypeFallback<O> {
+    fn default() -> Self {
+        Self {
+            policy: None,
+            default: None,
+        }
+    }
+}
+
+/// Finds `Default::default` of an output type if it exists
+#[doc(hidden)]
+pub struct DefaultOutput<O>(PhantomData<fn() -> O>);
+
+impl<O> DefaultOutput<O> {
+    pub const NEW: Self = Self(PhantomData);
+}
+
+impl<O: Default> DefaultOutput<O> {
+    // Inherent methods take precedence over `NoDefaultOutput::get` only if `O: Default`.
+    pub fn get(&self) -> Option<fn() -> O> {
+        Some(O::default)
+    }
+}
+
+#[doc(hidden)]
+pub trait NoDefaultOutput<O> {
+    fn get(&self) -> Option<fn() -> O> {
+        None
+    }
+}
+
+impl<O> NoDefaultOutput<O> for DefaultOutput<O> {}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __fallback {
+    ($output:ty) => {
+        $crate::__fallback!($output, None)
+    };
+    // For generic outputs, whose `Default` cannot be found by `DefaultOutput`
+    ($output:ty, default) => {
+        $crate::TypeFallback::<$output> {
+            policy: Some($crate::Fallback::Default),
+            default: Some(<$output as ::core::default::Default>::default),
+        }
+    };
+    ($output:ty, $policy:expr) => {{
+        #[allow(unused_imports)]
+        use $crate::NoDefaultOutput as _;
+        $crate::TypeFallback::<$output> {
+            policy: $policy,
+            default: $crate::DefaultOutput::<$output>::NEW.get(),
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct NotDefault;
+
+    #[test]
+    fn finds_default() {
+        assert_eq!(
+            crate::__fallback!(String).default.map(|f| f()),
+            Some(String::new())
+        );
+    }
+
+    #[test]
+    fn no_default() {
+        assert!(crate::__fallback!(NotDefault).default.is_none());
+    }
+
+    #[test]
+    fn requires_default() {
+        fn fallback<T: Default + PartialEq + std::fmt::Debug>() -> crate::TypeFallback<T> {
+            crate::__fallback!(T, default)
+        }
+        let fallback = fallback::<u8>();
+        assert_eq!(fallback.policy, Some(Fallback::Default));
+        assert_eq!(fallback.default.map(|f| f()), Some(0));
+    }
+
+    #[test]
+    fn policy() {
+        assert_eq!(
+            crate::__fallback!(String, Some(Fallback::CallsRealImpl)).policy,
+            Some(Fallback::CallsRealImpl)
+        );
+        assert_eq!(crate::__fallback!(String).policy, None);
+    }
+}
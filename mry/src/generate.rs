@@ -0,0 +1,74 @@
+use std::{
+    fmt::Debug,
+    hash::{BuildHasher, Hash, Hasher},
+    sync::OnceLock,
+};
+
+/// Environment variable to replay the outputs generated by `returns_arbitrary` and `returns_from`
+pub const SEED_ENV: &str = "MRY_SEED";
+
+/// The seed of the process, from `MRY_SEED` or random
+fn process_seed() -> u64 {
+    static SEED: OnceLock<u64> = OnceLock::new();
+    *SEED.get_or_init(|| match std::env::var(SEED_ENV) {
+        Ok(seed) => seed
+            .parse()
+            .unwrap_or_else(|_| panic!("{SEED_ENV} must be a u64, but it is `{seed}`")),
+        Err(_) => std::collections::hash_map::RandomState::new().hash_one(0u8),
+    })
+}
+
+/// Generates bytes for the outputs of a mocked method with splitmix64. The sequence depends only
+/// on the seed and the name of the method, so that a failure is replayed with the same seed.
+/// The values are not shrunk.
+pub(crate) struct Generator {
+    seed: u64,
+    state: u64,
+}
+
+impl Generator {
+    pub(crate) fn new(name: &'static str) -> Self {
+        let seed = process_seed();
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        (seed, name).hash(&mut hasher);
+        Self {
+            seed,
+            state: hasher.finish(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    pub(crate) fn fill(&mut self, bytes: &mut [u8]) {
+        for chunk in bytes.chunks_mut(8) {
+            chunk.copy_from_slice(&self.next_u64().to_le_bytes()[..chunk.len()]);
+        }
+    }
+
+    /// Describes the generated value with the seed replaying it, for the logs of the mock
+    pub(crate) fn describe(&self, value: &impl Debug) -> String {
+        format!("{value:?} (replay with {SEED_ENV}={})", self.seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_sequence_for_same_name() {
+        let bytes = |name| {
+            let mut bytes = [0; 20];
+            Generator::new(name).fill(&mut bytes);
+            bytes
+        };
+        assert_eq!(bytes("Cat::meow"), bytes("Cat::meow"));
+        assert_ne!(bytes("Cat::meow"), bytes("Cat::purr"));
+    }
+}
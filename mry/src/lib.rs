@@ -1,17 +1,35 @@
+mod by_ref;
+mod explore;
+mod fallback;
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+mod generate;
+pub mod matchers;
 mod mock;
+mod mock_fn;
 mod mock_locator;
 mod mockable;
 mod mocks;
 mod mry;
 mod rule;
 mod static_mocks;
+mod stream;
+mod sync;
 
 pub use crate::mry::*;
+pub use by_ref::*;
+pub use explore::*;
+pub use fallback::*;
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+pub use generate::SEED_ENV;
+pub use mock_fn::*;
 pub use mock_locator::*;
 pub use mocks::*;
-pub use mry_macros::{lock, m, mry, new};
+#[doc(hidden)]
+pub use mry_macros::__mock_supertrait;
+pub use mry_macros::{lock, m, mry, new, spy, test};
 pub use rule::*;
 pub use static_mocks::*;
+pub use stream::*;
 
 pub use rule::ArgMatcher::Any;
 
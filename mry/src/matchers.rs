@@ -0,0 +1,536 @@
+//! Matchers for arguments, used like `mock_open(mry::matchers::glob("*.json"))`
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    fmt,
+    sync::Arc,
+};
+
+use crate::ArgMatcher;
+
+/// Matches a string argument with a pattern. It converts into `ArgMatcher` of `String` and
+/// `SendWrapper<String>`, and into elements of `ArgMatcher<Vec<String>>` like
+/// `[glob("*.rs"), glob("*.toml")]`.
+#[derive(Clone)]
+pub struct StrPattern {
+    pattern: String,
+    matches: Arc<dyn Fn(&str) -> bool + Send + Sync>,
+}
+
+impl StrPattern {
+    pub fn matches(&self, input: &str) -> bool {
+        (self.matches)(input)
+    }
+}
+
+impl fmt::Debug for StrPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("StrPattern").field(&self.pattern).finish()
+    }
+}
+
+impl From<StrPattern> for ArgMatcher<String> {
+    fn from(pattern: StrPattern) -> Self {
+        ArgMatcher::Fn(Box::new(move |input: &String| pattern.matches(input)))
+    }
+}
+
+#[cfg(feature = "send_wrapper")]
+impl From<StrPattern> for ArgMatcher<crate::send_wrapper::SendWrapper<String>> {
+    fn from(pattern: StrPattern) -> Self {
+        ArgMatcher::Fn(Box::new(
+            move |input: &crate::send_wrapper::SendWrapper<String>| pattern.matches(input),
+        ))
+    }
+}
+
+/// Matches the whole string with a regular expression like `regex(r"^user-\d+$")`.
+/// Panics if the pattern is invalid.
+#[cfg(feature = "regex")]
+#[track_caller]
+pub fn regex(pattern: &str) -> StrPattern {
+    let regex =
+        regex::Regex::new(pattern).unwrap_or_else(|err| panic!("invalid regex `{pattern}`: {err}"));
+    StrPattern {
+        pattern: pattern.to_string(),
+        matches: Arc::new(move |input| regex.is_match(input)),
+    }
+}
+
+/// Matches the whole string with a glob pattern like `glob("*.json")`. `*` matches any
+/// characters, `?` matches one character, and `[a-z]` or `[!0-9]` matches one character in or
+/// not in the set. `\` escapes the next character.
+pub fn glob(pattern: &str) -> StrPattern {
+    let glob: Vec<char> = pattern.chars().collect();
+    StrPattern {
+        pattern: pattern.to_string(),
+        matches: Arc::new(move |input| {
+            let input: Vec<char> = input.chars().collect();
+            glob_matches(&glob, &input)
+        }),
+    }
+}
+
+fn glob_matches(pattern: &[char], input: &[char]) -> bool {
+    let (mut p, mut i) = (0, 0);
+    // Where to retry when the characters after the last `*` do not match
+    let mut star: Option<(usize, usize)> = None;
+    while i < input.len() {
+        let step = match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, i));
+                p += 1;
+                continue;
+            }
+            Some('?') => Some(1),
+            Some('[') => match_class(&pattern[p..], input[i]),
+            Some('\\') if p + 1 < pattern.len() => (pattern[p + 1] == input[i]).then_some(2),
+            Some(c) => (*c == input[i]).then_some(1),
+            None => None,
+        };
+        match (step, star) {
+            (Some(step), _) => {
+                p += step;
+                i += 1;
+            }
+            (None, Some((star_p, star_i))) => {
+                p = star_p + 1;
+                i = star_i + 1;
+                star = Some((star_p, star_i + 1));
+            }
+            (None, None) => return false,
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Returns the length of the class at the start of the pattern if it matches the character
+fn match_class(pattern: &[char], c: char) -> Option<usize> {
+    let negated = matches!(pattern.get(1), Some('!' | '^'));
+    let start = if negated { 2 } else { 1 };
+    // `]` right after `[` or `[!` is a member of the class
+    let end = start + 1 + pattern.get(start + 1..)?.iter().position(|c| *c == ']')?;
+    let class = &pattern[start..end];
+    let mut found = false;
+    let mut k = 0;
+    while k < class.len() {
+        if k + 2 < class.len() && class[k + 1] == '-' {
+            found |= (class[k]..=class[k + 2]).contains(&c);
+            k += 3;
+        } else {
+            found |= class[k] == c;
+            k += 1;
+        }
+    }
+    (found != negated).then_some(end + 1)
+}
+
+/// Matches an argument whose JSON serialization contains the given JSON, like
+/// `json_subset(json!({"user": {"id": 5}}))`. Objects may have other fields, while arrays must
+/// have the same length with each element matching.
+/// Panics when the argument cannot be serialized.
+#[cfg(feature = "serde")]
+pub fn json_subset<T: serde::Serialize>(expected: serde_json::Value) -> ArgMatcher<T> {
+    ArgMatcher::Fn(Box::new(move |input: &T| {
+        let actual = serde_json::to_value(input).unwrap_or_else(|err| {
+            panic!(
+                "`{}` cannot be serialized for `json_subset`: {err}",
+                std::any::type_name::<T>()
+            )
+        });
+        is_json_subset(&expected, &actual)
+    }))
+}
+
+#[cfg(feature = "serde")]
+fn is_json_subset(expected: &serde_json::Value, actual: &serde_json::Value) -> bool {
+    use serde_json::Value;
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => expected.iter().all(|(key, value)| {
+            actual
+                .get(key)
+                .is_some_and(|actual| is_json_subset(value, actual))
+        }),
+        (Value::Array(expected), Value::Array(actual)) => {
+            expected.len() == actual.len()
+                && expected
+                    .iter()
+                    .zip(actual)
+                    .all(|(expected, actual)| is_json_subset(expected, actual))
+        }
+        (expected, actual) => expected == actual,
+    }
+}
+
+/// Matches an argument with a predicate of the `predicates` crate like
+/// `predicate(predicate::gt(1).and(predicate::lt(5)))`. `String` arguments are matched by `str`
+/// predicates like `predicate::str::contains("a")`. The description of the predicate is shown
+/// in the message of a failed `assert_called`.
+#[cfg(feature = "predicates")]
+pub fn predicate<B, T, P>(predicate: P) -> ArgMatcher<T>
+where
+    B: ?Sized,
+    T: std::borrow::Borrow<B> + 'static,
+    P: predicates_core::Predicate<B> + Send + 'static,
+{
+    let description = predicate.to_string();
+    ArgMatcher::Fn(Box::new(move |input: &T| predicate.eval(input.borrow()))).described(description)
+}
+
+/// Matches an argument with a matcher of the `googletest` crate like
+/// `googletest(points_to(gt(1)))`. The matcher takes a reference to the argument, like
+/// `verify_that!(&value, ..)`, and must be `Send`. Its description is shown in the message of a
+/// failed `assert_called`.
+#[cfg(feature = "googletest")]
+pub fn googletest<T, M>(matcher: M) -> ArgMatcher<T>
+where
+    T: fmt::Debug + 'static,
+    M: for<'a> googletest::matcher::Matcher<&'a T> + Send + 'static,
+{
+    let description = matcher
+        .describe(googletest::matcher::MatcherResult::Match)
+        .to_string();
+    ArgMatcher::Fn(Box::new(move |input: &T| matcher.matches(input).is_match()))
+        .described(description)
+}
+
+/// Collections whose length is matched by `has_len` and `is_empty`
+pub trait Collection {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Collection for Vec<T> {
+    fn len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Collection for VecDeque<T> {
+    fn len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T, S> Collection for HashSet<T, S> {
+    fn len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Collection for BTreeSet<T> {
+    fn len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V, S> Collection for HashMap<K, V, S> {
+    fn len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V> Collection for BTreeMap<K, V> {
+    fn len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl Collection for String {
+    fn len(&self) -> usize {
+        self.len()
+    }
+}
+
+/// Maps whose entries are matched by `has_entry` and `has_key`
+pub trait Map {
+    type Key;
+    type Value;
+
+    fn entries(&self) -> Box<dyn Iterator<Item = (&Self::Key, &Self::Value)> + '_>;
+}
+
+impl<K, V, S> Map for HashMap<K, V, S> {
+    type Key = K;
+    type Value = V;
+
+    fn entries(&self) -> Box<dyn Iterator<Item = (&K, &V)> + '_> {
+        Box::new(self.iter())
+    }
+}
+
+impl<K, V> Map for BTreeMap<K, V> {
+    type Key = K;
+    type Value = V;
+
+    fn entries(&self) -> Box<dyn Iterator<Item = (&K, &V)> + '_> {
+        Box::new(self.iter())
+    }
+}
+
+/// Matches a collection with `n` elements
+pub fn has_len<C: Collection>(n: usize) -> ArgMatcher<C> {
+    ArgMatcher::Fn(Box::new(move |input: &C| input.len() == n))
+}
+
+/// Matches an empty collection
+pub fn is_empty<C: Collection>() -> ArgMatcher<C> {
+    ArgMatcher::Fn(Box::new(|input: &C| input.is_empty()))
+}
+
+/// Matches a `Vec` whose elements all match the matcher, including an empty one
+pub fn each<T: Send + 'static>(matcher: impl Into<ArgMatcher<T>>) -> ArgMatcher<Vec<T>> {
+    let matcher = matcher.into();
+    ArgMatcher::Fn(Box::new(move |input: &Vec<T>| {
+        input.iter().all(|elem| matcher.matches(elem))
+    }))
+}
+
+/// Matches a `Vec` whose elements match the matchers in any order, like `unordered(["b", "a"])`
+pub fn unordered<T: Send + 'static, M: Into<ArgMatcher<T>>>(
+    matchers: impl IntoIterator<Item = M>,
+) -> ArgMatcher<Vec<T>> {
+    let matchers: Vec<ArgMatcher<T>> = matchers.into_iter().map(Into::into).collect();
+    ArgMatcher::Fn(Box::new(move |input: &Vec<T>| {
+        input.len() == matchers.len() && match_all(&matchers, input)
+    }))
+}
+
+/// Matches a `Vec` that has distinct elements matching each of the matchers in any order
+pub fn contains_all<T: Send + 'static, M: Into<ArgMatcher<T>>>(
+    matchers: impl IntoIterator<Item = M>,
+) -> ArgMatcher<Vec<T>> {
+    let matchers: Vec<ArgMatcher<T>> = matchers.into_iter().map(Into::into).collect();
+    ArgMatcher::Fn(Box::new(move |input: &Vec<T>| match_all(&matchers, input)))
+}
+
+/// Matches a map that has an entry matching both of the matchers, like `has_entry("id", 1)`
+pub fn has_entry<M: Map + 'static>(
+    key: impl Into<ArgMatcher<M::Key>>,
+    value: impl Into<ArgMatcher<M::Value>>,
+) -> ArgMatcher<M>
+where
+    M::Key: Send + 'static,
+    M::Value: Send + 'static,
+{
+    let (key, value) = (key.into(), value.into());
+    ArgMatcher::Fn(Box::new(move |input: &M| {
+        input
+            .entries()
+            .any(|(k, v)| key.matches(k) && value.matches(v))
+    }))
+}
+
+/// Matches a map that has a key matching the matcher
+pub fn has_key<M: Map + 'static>(key: impl Into<ArgMatcher<M::Key>>) -> ArgMatcher<M>
+where
+    M::Key: Send + 'static,
+{
+    let key = key.into();
+    ArgMatcher::Fn(Box::new(move |input: &M| {
+        input.entries().any(|(k, _)| key.matches(k))
+    }))
+}
+
+/// Whether each matcher can be assigned a distinct element it matches, found with augmenting
+/// paths because a matcher like `mry::Any` may take the element another matcher needs
+fn match_all<T>(matchers: &[ArgMatcher<T>], input: &[T]) -> bool {
+    fn assign<T>(
+        matcher: usize,
+        matchers: &[ArgMatcher<T>],
+        input: &[T],
+        owners: &mut [Option<usize>],
+        visited: &mut [bool],
+    ) -> bool {
+        for (index, elem) in input.iter().enumerate() {
+            if visited[index] || !matchers[matcher].matches(elem) {
+                continue;
+            }
+            visited[index] = true;
+            let free = match owners[index] {
+                Some(owner) => assign(owner, matchers, input, owners, visited),
+                None => true,
+            };
+            if free {
+                owners[index] = Some(matcher);
+                return true;
+            }
+        }
+        false
+    }
+    if matchers.len() > input.len() {
+        return false;
+    }
+    let mut owners = vec![None; input.len()];
+    (0..matchers.len()).all(|matcher| {
+        let mut visited = vec![false; input.len()];
+        assign(matcher, matchers, input, &mut owners, &mut visited)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_wildcards() {
+        assert!(glob("*.json").matches("a.json"));
+        assert!(glob("*.json").matches(".json"));
+        assert!(!glob("*.json").matches("a.json.bak"));
+        assert!(glob("a*b*c").matches("aXbYbc"));
+        assert!(glob("user-??").matches("user-42"));
+        assert!(!glob("user-??").matches("user-4"));
+        assert!(glob("*").matches(""));
+        assert!(glob("").matches(""));
+        assert!(!glob("").matches("a"));
+    }
+
+    #[test]
+    fn glob_classes() {
+        assert!(glob("[a-c]at").matches("bat"));
+        assert!(!glob("[a-c]at").matches("cut"));
+        assert!(!glob("[!0-9]*").matches("1st"));
+        assert!(glob("[!0-9]*").matches("first"));
+        assert!(glob("[]]").matches("]"));
+        assert!(glob(r"\*.md").matches("*.md"));
+        assert!(!glob(r"\*.md").matches("a.md"));
+        assert!(!glob("[ab").matches("a"));
+    }
+
+    #[test]
+    fn glob_non_ascii() {
+        assert!(glob("猫?").matches("猫a"));
+        assert!(glob("*ñ").matches("año ñ"));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex_matches() {
+        let matcher: ArgMatcher<String> = regex(r"^user-\d+$").into();
+        assert!(matcher.matches(&"user-42".to_string()));
+        assert!(!matcher.matches(&"user-x".to_string()));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    #[should_panic(expected = "invalid regex `(`")]
+    fn invalid_regex() {
+        regex("(");
+    }
+
+    #[test]
+    fn vec_elements() {
+        let matcher: ArgMatcher<Vec<String>> = [glob("*.rs"), glob("*.toml")].into();
+        assert!(matcher.matches(&vec!["lib.rs".to_string(), "Cargo.toml".to_string()]));
+        assert!(!matcher.matches(&vec!["lib.rs".to_string(), "Cargo.lock".to_string()]));
+    }
+
+    #[cfg(feature = "send_wrapper")]
+    #[test]
+    fn send_wrapper() {
+        let matcher: ArgMatcher<crate::send_wrapper::SendWrapper<String>> = glob("a*").into();
+        assert!(matcher.matches(&crate::send_wrapper::SendWrapper::new("abc".to_string())));
+        assert!(!matcher.matches(&crate::send_wrapper::SendWrapper::new("cba".to_string())));
+    }
+
+    #[test]
+    fn collection_len() {
+        assert!(has_len::<Vec<u8>>(2).matches(&vec![1, 2]));
+        assert!(!has_len::<Vec<u8>>(2).matches(&vec![1]));
+        assert!(is_empty::<HashMap<String, u8>>().matches(&HashMap::new()));
+        assert!(!is_empty::<String>().matches(&"a".to_string()));
+    }
+
+    #[test]
+    fn each_element() {
+        let matcher: ArgMatcher<Vec<String>> = each(glob("*.rs"));
+        assert!(matcher.matches(&vec!["a.rs".to_string(), "b.rs".to_string()]));
+        assert!(matcher.matches(&vec![]));
+        assert!(!matcher.matches(&vec!["a.rs".to_string(), "b.md".to_string()]));
+    }
+
+    #[test]
+    fn unordered_elements() {
+        let matcher: ArgMatcher<Vec<String>> = unordered(["b", "a"]);
+        assert!(matcher.matches(&vec!["a".to_string(), "b".to_string()]));
+        assert!(!matcher.matches(&vec!["a".to_string(), "a".to_string()]));
+        assert!(!matcher.matches(&vec!["a".to_string(), "b".to_string(), "c".to_string()]));
+    }
+
+    #[test]
+    fn unordered_reassigns_overlapping_matchers() {
+        // `Any` takes 1 first, and gives it up for `1` to take
+        let matcher: ArgMatcher<Vec<u8>> = unordered([ArgMatcher::Any, 1.into()]);
+        assert!(matcher.matches(&vec![1, 2]));
+        assert!(!matcher.matches(&vec![2, 3]));
+    }
+
+    #[test]
+    fn contains_all_elements() {
+        let matcher: ArgMatcher<Vec<u8>> = contains_all([3, 1]);
+        assert!(matcher.matches(&vec![1, 2, 3]));
+        assert!(!matcher.matches(&vec![1, 2]));
+        let matcher: ArgMatcher<Vec<u8>> = contains_all([1, 1]);
+        assert!(!matcher.matches(&vec![1, 2]));
+        assert!(matcher.matches(&vec![1, 2, 1]));
+    }
+
+    #[test]
+    fn map_entries() {
+        let map = HashMap::from([("id".to_string(), 1u8), ("age".to_string(), 3)]);
+        assert!(has_entry::<HashMap<String, u8>>("id", 1).matches(&map));
+        assert!(!has_entry::<HashMap<String, u8>>("id", 3).matches(&map));
+        assert!(has_key::<BTreeMap<String, u8>>(glob("a*")).matches(&map.into_iter().collect()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_subset_of_argument() {
+        use serde_json::json;
+        let matcher: ArgMatcher<serde_json::Value> =
+            json_subset(json!({"user": {"id": 5}, "tags": [{"name": "a"}]}));
+        assert!(matcher.matches(&json!({
+            "user": {"id": 5, "name": "Tama"},
+            "tags": [{"name": "a", "color": "red"}],
+            "at": 123,
+        })));
+        assert!(!matcher.matches(&json!({"user": {"id": 6}, "tags": [{"name": "a"}]})));
+        assert!(!matcher.matches(&json!({"user": {"id": 5}, "tags": []})));
+        assert!(!matcher.matches(&json!({"user": {"id": 5}})));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    #[should_panic(expected = "cannot be serialized for `json_subset`")]
+    fn json_subset_of_unserializable_argument() {
+        let matcher: ArgMatcher<HashMap<(u8, u8), u8>> = json_subset(serde_json::json!({}));
+        matcher.matches(&HashMap::from([((1, 2), 3)]));
+    }
+
+    #[cfg(feature = "predicates")]
+    #[test]
+    fn predicates_adapter() {
+        use predicates::prelude::*;
+        let matcher: ArgMatcher<u8> = predicate(predicate::gt(1).and(predicate::lt(5)));
+        assert!(matcher.matches(&3));
+        assert!(!matcher.matches(&5));
+        assert_eq!(matcher.description(), Some("(var > 1 && var < 5)"));
+        let matcher: ArgMatcher<String> = predicate(predicate::str::contains("a"));
+        assert!(matcher.matches(&"cat".to_string()));
+    }
+
+    #[cfg(feature = "googletest")]
+    #[test]
+    fn googletest_adapter() {
+        use googletest::prelude::*;
+        let matcher: ArgMatcher<u8> = super::googletest(points_to(gt(1)));
+        assert!(matcher.matches(&3));
+        assert!(!matcher.matches(&1));
+        assert_eq!(matcher.description(), Some("is greater than 1"));
+        let matcher: ArgMatcher<String> = super::googletest(eq("cat"));
+        assert!(matcher.matches(&"cat".to_string()));
+    }
+}
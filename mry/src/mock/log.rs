@@ -4,41 +4,77 @@ use parking_lot::Mutex;
 
 use crate::{times::Times, Matcher};
 
-pub struct Logs<I>(Vec<Arc<Mutex<I>>>);
+pub struct Logs<I> {
+    calls: Vec<Arc<Mutex<I>>>,
+    /// Outputs of `returns_arbitrary` and the like, shared with the behaviors generating them
+    generated: Arc<Mutex<Vec<String>>>,
+}
 
 impl<I> Logs<I> {
     pub(crate) fn push(&mut self, item: Arc<Mutex<I>>) {
-        self.0.push(item);
+        self.calls.push(item);
     }
 
     pub fn filter_matches(&self, matcher: &Matcher<I>) -> Self {
-        Self(
-            self.0
+        Self {
+            calls: self
+                .calls
                 .iter()
                 .filter(|log| matcher.matches(&log.lock()))
                 .cloned()
                 .collect(),
-        )
+            generated: self.generated.clone(),
+        }
+    }
+
+    /// Generated outputs in the order of the calls, like `3 (replay with MRY_SEED=1234)`
+    pub fn generated(&self) -> Vec<String> {
+        self.generated.lock().clone()
+    }
+
+    #[cfg(any(feature = "arbitrary", feature = "proptest"))]
+    pub(crate) fn generated_sink(&self) -> Arc<Mutex<Vec<String>>> {
+        self.generated.clone()
+    }
+
+    fn describe_generated(&self) -> String {
+        let generated = self.generated.lock();
+        if generated.is_empty() {
+            String::new()
+        } else {
+            format!(". Generated outputs: {}", generated.join(", "))
+        }
     }
 
     #[track_caller]
     pub(crate) fn assert_called(&self, name: &str, matcher: &Matcher<I>, times: Times) -> Self {
         let logs = self.filter_matches(matcher);
-        let actual = logs.0.len();
+        let actual = logs.calls.len();
         if !times.contains(&actual) {
-            panic!("Expected {name} to be called {times} times, but it was called {actual} times",);
+            let generated = self.describe_generated();
+            match matcher.describe() {
+                Some(arguments) => panic!(
+                    "Expected {name} to be called {times} times with {arguments}, but it was called {actual} times{generated}"
+                ),
+                None => panic!(
+                    "Expected {name} to be called {times} times, but it was called {actual} times{generated}"
+                ),
+            }
         }
         logs
     }
 
     pub fn iter(&self) -> impl Iterator<Item = impl Deref<Target = I> + '_> {
-        self.0.iter().map(|log| log.lock())
+        self.calls.iter().map(|log| log.lock())
     }
 }
 
 impl<I> Default for Logs<I> {
     fn default() -> Self {
-        Self(Default::default())
+        Self {
+            calls: Default::default(),
+            generated: Default::default(),
+        }
     }
 }
 
@@ -57,6 +93,20 @@ mod test {
         let matcher = Matcher::new_eq(2);
 
         let filtered = logs.filter_matches(&matcher);
-        assert_eq!(filtered.0.len(), 2);
+        assert_eq!(filtered.calls.len(), 2);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Expected meow to be called 2 times, but it was called 1 times. Generated outputs: 3, 5"
+    )]
+    fn assert_called_shows_generated() {
+        let mut logs = Logs::default();
+        logs.push(Arc::new(Mutex::new(1)));
+        logs.generated
+            .lock()
+            .extend(["3".to_string(), "5".to_string()]);
+
+        logs.assert_called("meow", &Matcher::any(), 2.into());
     }
 }
@@ -5,22 +5,73 @@ pub use log::*;
 
 use parking_lot::Mutex;
 
-use crate::{times::Times, Behavior, Matcher, MockableRet, Output, Rule};
+use crate::{times::Times, Behavior, Matcher, MockableRet, Output, Receiver, Rule, RuleBehavior};
 
 pub struct Mock<I, O> {
     pub name: &'static str,
     pub log: Logs<I>,
+    object_name: ObjectName,
     rules: Vec<Rule<I, O>>,
 }
 
 impl<I, O> Mock<I, O> {
     pub fn new(name: &'static str) -> Self {
+        Self::with_object_name(name, Default::default())
+    }
+
+    pub(crate) fn with_object_name(name: &'static str, object_name: ObjectName) -> Self {
         Self {
             name,
             log: Default::default(),
+            object_name,
             rules: Default::default(),
         }
     }
+
+    /// Copies the rules into a mock with no calls, for the object named `object_name`
+    #[cfg(debug_assertions)]
+    pub(crate) fn fork(&self, object_name: ObjectName) -> Self {
+        Self {
+            name: self.name,
+            log: Default::default(),
+            object_name,
+            rules: self.rules.clone(),
+        }
+    }
+
+    /// Name of the method with the name of the mock object if it has
+    pub(crate) fn describe(&self) -> String {
+        self.object_name.describe(self.name)
+    }
+}
+
+/// Name of a mock object given by `Mry::set_name`, shared by the mocks of its methods
+#[derive(Clone, Default)]
+pub(crate) struct ObjectName(Arc<Mutex<Option<String>>>);
+
+impl ObjectName {
+    pub(crate) fn set(&self, name: String) {
+        *self.0.lock() = Some(name);
+    }
+
+    #[cfg(debug_assertions)]
+    pub(crate) fn get(&self) -> Option<String> {
+        self.0.lock().clone()
+    }
+
+    /// Copies the name, which is renamed independently of this one
+    #[cfg(debug_assertions)]
+    pub(crate) fn fork(&self) -> Self {
+        Self(Arc::new(Mutex::new(self.get())))
+    }
+
+    /// Describes a method of the object like `Storage::get (primary)`
+    pub(crate) fn describe(&self, method: &str) -> String {
+        match &*self.0.lock() {
+            Some(name) => format!("{method} ({name})"),
+            None => method.to_string(),
+        }
+    }
 }
 
 impl<I, O> Mock<I, O> {
@@ -29,7 +80,7 @@ impl<I, O> Mock<I, O> {
         matcher: Arc<Mutex<Matcher<I>>>,
         behavior: Behavior<I, O>,
     ) {
-        self.rules.push(Rule { matcher, behavior });
+        self.rules.push(Rule::new(matcher, behavior));
     }
 
     pub(crate) fn returns_once(&mut self, matcher: Arc<Mutex<Matcher<I>>>, ret: O) {
@@ -37,17 +88,14 @@ impl<I, O> Mock<I, O> {
     }
 
     pub(crate) fn calls_real_impl(&mut self, matcher: Arc<Mutex<Matcher<I>>>) {
-        self.rules.push(Rule {
-            matcher,
-            behavior: Behavior::CallsRealImpl,
-        })
+        self.returns_with(matcher, Behavior::CallsRealImpl)
     }
 }
 
 impl<I, O> Mock<I, O> {
     #[track_caller]
     pub(crate) fn assert_called(&self, matcher: &Matcher<I>, times: Times) {
-        self.log.assert_called(self.name, matcher, times);
+        self.log.assert_called(&self.describe(), matcher, times);
     }
 
     pub(crate) fn record_call(&mut self, input: Arc<Mutex<I>>) {
@@ -56,21 +104,64 @@ impl<I, O> Mock<I, O> {
 }
 
 impl<I, O> Mock<I, O> {
+    /// Records the call and finds the first rule matching the input.
+    /// The behavior of the rule is not called here, so that it can call the mocks again.
+    pub(crate) fn record_call_and_find_rule(&mut self, input: I) -> Option<MatchedCall<I, O>> {
+        let behavior = self
+            .rules
+            .iter()
+            .find(|rule| rule.matches(&input))
+            .map(|rule| rule.behavior.clone());
+        let input = Arc::new(Mutex::new(input));
+        self.record_call(input.clone());
+        behavior.map(|behavior| MatchedCall {
+            name: self.name,
+            object_name: self.object_name.clone(),
+            behavior,
+            input,
+        })
+    }
+}
+
+#[cfg(test)]
+impl<I: Clone, O> Mock<I, O> {
     #[track_caller]
     pub(crate) fn find_mock_output(&mut self, input: &I) -> Option<O> {
-        for rule in &mut self.rules {
-            if !rule.matches(input) {
-                continue;
+        self.record_call_and_find_rule(input.clone())
+            .unwrap_or_else(|| panic!("mock not found for {}", self.describe()))
+            .output(None)
+    }
+}
+
+/// A call matched with a rule
+pub(crate) struct MatchedCall<I, O> {
+    name: &'static str,
+    object_name: ObjectName,
+    behavior: Arc<RuleBehavior<I, O>>,
+    input: Arc<Mutex<I>>,
+}
+
+impl<I, O> MatchedCall<I, O> {
+    /// Calls the behavior of the rule. Returns `None` if the real implementation should be called.
+    #[track_caller]
+    pub(crate) fn output(self, receiver: Option<Receiver<'_>>) -> Option<O> {
+        // A `FnMut` cannot be called again while it runs
+        if self.behavior.is_running() {
+            panic!(
+                "behavior for {} is already running on this thread",
+                self.object_name.describe(self.name)
+            );
+        }
+        match self.behavior.call(&self.input, receiver) {
+            Output::Found(output) => Some(output),
+            Output::CallsRealImpl => None,
+            Output::ErrorCalledOnce => {
+                panic!(
+                    "{} was called more than once",
+                    self.object_name.describe(self.name)
+                )
             }
-            return match rule.call_behavior(input) {
-                Output::Found(output) => Some(output),
-                Output::CallsRealImpl => None,
-                Output::ErrorCalledOnce => {
-                    panic!("{} was called more than once", self.name)
-                }
-            };
         }
-        panic!("mock not found for {}", self.name)
     }
 }
 
@@ -167,6 +258,19 @@ mod test {
         mock.find_mock_output(&(2,));
     }
 
+    #[test]
+    fn record_call_and_find_rule_returns_none_without_rule() {
+        let mut mock = Mock::<(usize,), String>::new("a");
+        mock.returns(Matcher::new_eq((3,)).wrapped(), "a".repeat(3));
+
+        assert!(mock.record_call_and_find_rule((2,)).is_none());
+        assert_eq!(
+            mock.record_call_and_find_rule((3,)).unwrap().output(None),
+            Some("aaa".to_string())
+        );
+        assert_eq!(mock.log.iter().count(), 2);
+    }
+
     #[test]
     #[should_panic(expected = "a was called more than once")]
     fn panic_on_once_called_multiple_time() {
@@ -0,0 +1,123 @@
+use std::{any::TypeId, marker::PhantomData, sync::Arc};
+
+use crate::{mockable::MockableArg, sync::Mutex, MockableRet, Mocks, TypeFallback};
+
+const NAME: &str = "mock_fn";
+
+/// Creates a closure recording its calls and returning the output of matching rules, with a
+/// handle to set the rules like `handle.mock(Event::Click).returns(true)`.
+/// Messages name the closure by the location of this call, like `mock_fn (tests/a.rs:3:26)`.
+#[track_caller]
+pub fn mock_fn<I: MockFnInput<O>, O>() -> (I::Fn, MockFn<I, O>) {
+    let mocks = Mocks::default();
+    mocks.name.set(std::panic::Location::caller().to_string());
+    let mocks = Arc::new(Mutex::new(mocks));
+    (
+        I::mock_fn(mocks.clone()),
+        MockFn {
+            mocks,
+            _phantom: PhantomData,
+        },
+    )
+}
+
+/// Handle of a closure created by [`mock_fn`].
+/// `mock(..)` takes matchers of the arguments and returns [`MockLocator`](crate::MockLocator).
+pub struct MockFn<I, O> {
+    mocks: Arc<Mutex<Mocks>>,
+    _phantom: PhantomData<fn(I) -> O>,
+}
+
+impl<I, O> MockFn<I, O> {
+    /// Names the closure in panic and assertion messages instead of the location, like `mock_fn (on_click)`
+    pub fn set_name(&self, name: impl Into<String>) -> &Self {
+        self.mocks.lock().name.set(name.into());
+        self
+    }
+}
+
+impl<I, O> Clone for MockFn<I, O> {
+    fn clone(&self) -> Self {
+        Self {
+            mocks: self.mocks.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// Argument tuples of closures created by [`mock_fn`]
+#[doc(hidden)]
+pub trait MockFnInput<O>: Sized {
+    type Fn;
+
+    fn mock_fn(mocks: Arc<Mutex<Mocks>>) -> Self::Fn;
+}
+
+fn key<I: 'static, O: 'static>() -> TypeId {
+    TypeId::of::<MockFn<I, O>>()
+}
+
+#[track_caller]
+fn call<I: MockableArg, O: MockableRet>(mocks: &Mutex<Mocks>, input: I) -> O {
+    // Releases the lock before calling the behavior, which may call the closure again.
+    let output =
+        mocks
+            .lock()
+            .record_call_and_find_rule(key::<I, O>(), NAME, input, TypeFallback::default());
+    output
+        .output(None)
+        .unwrap_or_else(|| panic!("{NAME} has no real implementation to call"))
+}
+
+mry_macros::create_mock_fns!();
+
+#[cfg(test)]
+mod tests {
+    use crate::Any;
+
+    use super::*;
+
+    #[test]
+    fn returns_output_of_rules() {
+        let (f, handle) = mock_fn::<(u8, String), usize>();
+        handle.mock(1, "a").returns(1);
+        handle
+            .mock(Any, Any)
+            .returns_with(|a, b: String| a as usize + b.len());
+
+        assert_eq!(f(1, "a".into()), 1);
+        assert_eq!(f(2, "ab".into()), 4);
+        handle.mock(Any, Any).assert_called(2);
+    }
+
+    #[test]
+    fn no_arguments() {
+        let (f, handle) = mock_fn::<(), u8>();
+        handle.mock().returns_once(1);
+
+        assert_eq!(f(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "mock not found for mock_fn (mry/src/mock_fn.rs:")]
+    fn panics_with_no_rules() {
+        let (f, _handle) = mock_fn::<(u8,), u8>();
+        f(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected mock_fn (on_click) to be called 1 times")]
+    fn named() {
+        let (_f, handle) = mock_fn::<(u8,), u8>();
+        handle.set_name("on_click");
+        handle.mock(Any).assert_called(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "mock_fn has no real implementation to call")]
+    fn calls_real_impl() {
+        let (f, handle) = mock_fn::<(u8,), u8>();
+        handle.mock(Any).calls_real_impl();
+        f(1);
+    }
+}
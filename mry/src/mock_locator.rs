@@ -5,13 +5,16 @@ use std::{any::TypeId, marker::PhantomData};
 
 use parking_lot::Mutex;
 
-use crate::{mockable::MockableRet, Behavior, Matcher, MockGetter};
+use crate::{
+    mockable::MockableRet, sync, Behavior, FromItems, Items, Matcher, MockGetter, Receiver,
+    WithArgs, WithSelf, WithSelfMut,
+};
 
 use self::times::Times;
 
 /// Mock locator returned by mock_* methods
 pub struct MockLocator<I, O, R, B> {
-    pub(crate) mocks: Arc<Mutex<dyn MockGetter<I, O>>>,
+    pub(crate) mocks: Arc<sync::Mutex<dyn MockGetter<I, O>>>,
     pub(crate) key: TypeId,
     pub(crate) name: &'static str,
     pub(crate) matcher: Arc<Mutex<Matcher<I>>>,
@@ -22,7 +25,7 @@ pub struct MockLocator<I, O, R, B> {
 impl<I, O, R, B> MockLocator<I, O, R, B> {
     #[doc(hidden)]
     pub fn new(
-        mocks: Arc<Mutex<dyn MockGetter<I, O>>>,
+        mocks: Arc<sync::Mutex<dyn MockGetter<I, O>>>,
         key: TypeId,
         name: &'static str,
         matcher: Matcher<I>,
@@ -57,6 +60,97 @@ where
     }
 }
 
+impl<I, O, R, B> MockLocator<I, O, R, B>
+where
+    I: Clone + 'static,
+    R: 'static,
+    O: 'static,
+{
+    /// Returns value with using a closure that takes the receiver of the method like `|this: &Cat, count| ..`.
+    /// The closure can read fields of the object and call its other methods.
+    pub fn returns_with_self<S: 'static, T: Into<WithSelf<S, I, R>>>(self, behavior: T) -> Self {
+        let mut call = behavior.into().0;
+        let (name, ret_to_out) = (self.name, self.ret_to_out);
+        get_mut_or_default!(self).returns_with(
+            self.matcher.clone(),
+            Behavior::WithReceiver {
+                clone: Clone::clone,
+                call: Box::new(move |receiver, input| {
+                    ret_to_out(call(Receiver::get(receiver, name), input))
+                }),
+            },
+        );
+        self
+    }
+
+    /// Same as [`MockLocator::returns_with_self`], but for `&mut self` methods with a closure like `|this: &mut Cat, count| ..`.
+    pub fn returns_with_self_mut<S: 'static, T: Into<WithSelfMut<S, I, R>>>(
+        self,
+        behavior: T,
+    ) -> Self {
+        let mut call = behavior.into().0;
+        let (name, ret_to_out) = (self.name, self.ret_to_out);
+        get_mut_or_default!(self).returns_with(
+            self.matcher.clone(),
+            Behavior::WithReceiver {
+                clone: Clone::clone,
+                call: Box::new(move |receiver, input| {
+                    ret_to_out(call(Receiver::get_mut(receiver, name), input))
+                }),
+            },
+        );
+        self
+    }
+}
+
+impl<I, O, R, B> MockLocator<I, O, R, B>
+where
+    I: Clone + 'static,
+    O: 'static,
+    R: 'static,
+{
+    /// Returns an iterator or a stream of the items for each call, like
+    /// `returns_stream([1, 2])` or `returns_stream(Items::new([Ok(1)]).ending_with_error(e))`.
+    pub fn returns_stream<T>(self, items: impl Into<Items<T>>) -> Self
+    where
+        T: Clone + Send + 'static,
+        R: FromItems<T>,
+    {
+        let items = items.into();
+        self.returns_stream_with_items(move |_| items.clone())
+    }
+
+    /// Returns an iterator or a stream of the items returned by the closure, which takes the
+    /// arguments like the one of `returns_with`.
+    pub fn returns_stream_with<T, X, F>(self, f: F) -> Self
+    where
+        F: Into<WithArgs<I, X>>,
+        X: Into<Items<T>> + 'static,
+        R: FromItems<T>,
+    {
+        let mut call = f.into().0;
+        self.returns_stream_with_items(move |input| call(input).into())
+    }
+
+    fn returns_stream_with_items<T>(
+        self,
+        mut items: impl FnMut(I) -> Items<T> + Send + 'static,
+    ) -> Self
+    where
+        R: FromItems<T>,
+    {
+        let ret_to_out = self.ret_to_out;
+        get_mut_or_default!(self).returns_with(
+            self.matcher.clone(),
+            Behavior::Function {
+                clone: Clone::clone,
+                call: Box::new(move |input| ret_to_out(R::from_items(items(input)))),
+            },
+        );
+        self
+    }
+}
+
 impl<I, O, R, B> MockLocator<I, O, R, B> {
     /// Returns value once. After that, it panics.
     pub fn returns_once(self, ret: R) -> Self {
@@ -77,6 +171,142 @@ impl<I, O, R, B> MockLocator<I, O, R, B> {
     pub fn assert_called(&self, times: impl Into<Times>) {
         get_mut_or_default!(self).assert_called(&self.matcher.lock(), times.into());
     }
+
+    /// Outputs generated by `returns_arbitrary`, `returns_arbitrary_from`, or `returns_from`
+    /// for the calls, with the seeds replaying them
+    #[cfg(any(feature = "arbitrary", feature = "proptest"))]
+    pub fn generated(&self) -> Vec<String> {
+        get_mut_or_default!(self).log.generated()
+    }
+}
+
+impl<I, O, R, B> MockLocator<I, O, R, B>
+where
+    O: 'static,
+    R: Clone + std::fmt::Debug + Send + 'static,
+{
+    /// Returns one of the alternatives for each call. In [`crate::explore`], the runs take every
+    /// combination of them. Otherwise, it returns the first one.
+    #[track_caller]
+    pub fn returns_one_of(self, alternatives: impl IntoIterator<Item = R>) -> Self {
+        let alternatives: Vec<_> = alternatives.into_iter().collect();
+        let (name, ret_to_out) = (self.name, self.ret_to_out);
+        if alternatives.is_empty() {
+            panic!("`returns_one_of` for {name} needs at least one alternative");
+        }
+        let run = crate::explore::current();
+        let outputs = std::iter::repeat_with(move || {
+            let index = run
+                .as_ref()
+                .map_or(0, |run| run.lock().choose(name, &alternatives));
+            ret_to_out(alternatives[index].clone())
+        });
+        get_mut_or_default!(self).returns_with(
+            self.matcher.clone(),
+            Behavior::Const(Mutex::new(Box::new(outputs))),
+        );
+        self
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<I, O, R, B> MockLocator<I, O, R, B>
+where
+    O: 'static,
+    R: for<'a> arbitrary::Arbitrary<'a> + std::fmt::Debug + 'static,
+{
+    /// Returns a value generated with `arbitrary` for each call. The values are logged with the
+    /// seed, and `MRY_SEED` replays them.
+    pub fn returns_arbitrary(self) -> Self {
+        let mut generator = crate::generate::Generator::new(self.name);
+        let ret_to_out = self.ret_to_out;
+        let mut mocks = self.mocks.lock();
+        let mock = mocks.get_mut_or_create(self.key, self.name);
+        let generated = mock.log.generated_sink();
+        let outputs = std::iter::repeat_with(move || {
+            let mut bytes = vec![0; 4096];
+            generator.fill(&mut bytes);
+            let value = R::arbitrary_take_rest(arbitrary::Unstructured::new(&bytes))
+                .unwrap_or_else(|err| panic!("failed to generate an arbitrary value: {err}"));
+            generated.lock().push(generator.describe(&value));
+            ret_to_out(value)
+        });
+        mock.returns_with(
+            self.matcher.clone(),
+            Behavior::Const(Mutex::new(Box::new(outputs))),
+        );
+        drop(mocks);
+        self
+    }
+
+    /// Returns values generated with `arbitrary` from the given data, like the input of a fuzz
+    /// target. The calls take the data in order, and get default-like values after it runs out.
+    pub fn returns_arbitrary_from(self, data: impl Into<Vec<u8>>) -> Self {
+        let (data, ret_to_out) = (data.into(), self.ret_to_out);
+        let mut mocks = self.mocks.lock();
+        let mock = mocks.get_mut_or_create(self.key, self.name);
+        let generated = mock.log.generated_sink();
+        let mut offset = 0;
+        let outputs = std::iter::repeat_with(move || {
+            let mut unstructured = arbitrary::Unstructured::new(&data[offset..]);
+            let value = R::arbitrary(&mut unstructured)
+                .unwrap_or_else(|err| panic!("failed to generate an arbitrary value: {err}"));
+            offset = data.len() - unstructured.len();
+            // Replayed with the same data rather than a seed
+            generated.lock().push(format!("{value:?}"));
+            ret_to_out(value)
+        });
+        mock.returns_with(
+            self.matcher.clone(),
+            Behavior::Const(Mutex::new(Box::new(outputs))),
+        );
+        drop(mocks);
+        self
+    }
+}
+
+#[cfg(feature = "proptest")]
+impl<I, O, R, B> MockLocator<I, O, R, B>
+where
+    O: 'static,
+    R: std::fmt::Debug + 'static,
+{
+    /// Returns a value generated by the `proptest` strategy for each call. The values are logged
+    /// with the seed, and `MRY_SEED` replays them.
+    pub fn returns_from<S>(self, strategy: S) -> Self
+    where
+        S: proptest::strategy::Strategy<Value = R> + Send + 'static,
+    {
+        use proptest::{
+            strategy::ValueTree,
+            test_runner::{Config, RngAlgorithm, TestRng, TestRunner},
+        };
+        let mut generator = crate::generate::Generator::new(self.name);
+        let ret_to_out = self.ret_to_out;
+        let mut mocks = self.mocks.lock();
+        let mock = mocks.get_mut_or_create(self.key, self.name);
+        let generated = mock.log.generated_sink();
+        let outputs = std::iter::repeat_with(move || {
+            let mut seed = [0; 32];
+            generator.fill(&mut seed);
+            let mut runner = TestRunner::new_with_rng(
+                Config::default(),
+                TestRng::from_seed(RngAlgorithm::ChaCha, &seed),
+            );
+            let value = strategy
+                .new_tree(&mut runner)
+                .unwrap_or_else(|err| panic!("failed to generate a value: {err}"))
+                .current();
+            generated.lock().push(generator.describe(&value));
+            ret_to_out(value)
+        });
+        mock.returns_with(
+            self.matcher.clone(),
+            Behavior::Const(Mutex::new(Box::new(outputs))),
+        );
+        drop(mocks);
+        self
+    }
 }
 
 impl<I, O, R, B> MockLocator<I, O, R, B>
@@ -1,12 +1,12 @@
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
+use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
 use std::sync::Arc;
 
-use parking_lot::Mutex;
-
-use crate::mock::Mock;
+use crate::mock::{MatchedCall, Mock, ObjectName};
 use crate::mockable::{MockableArg, MockableRet};
+use crate::{Fallback, Output, Receiver, RuleBehavior, TypeFallback};
 
 type BoxAnySend = Box<dyn Any + Send>;
 
@@ -35,43 +35,201 @@ where
 #[derive(Default)]
 #[doc(hidden)]
 pub struct Mocks {
-    pub(crate) mock_objects: HashMap<TypeId, BoxAnySend>,
+    pub(crate) mock_objects: HashMap<TypeId, Forkable>,
+    /// Overrides the fallback given by `#[mry::mry(fallback = ..)]`
+    pub(crate) fallback: Option<Fallback>,
+    /// Outputs for calls with no matching rule, keyed by the type of the output
+    pub(crate) fallback_outputs: HashMap<TypeId, Forkable>,
+    pub(crate) name: ObjectName,
+}
+
+/// A type-erased value with the function copying it for [`Mocks::fork`]
+pub(crate) struct Forkable {
+    value: BoxAnySend,
+    #[cfg(debug_assertions)]
+    fork: fn(&BoxAnySend, &ObjectName) -> BoxAnySend,
+}
+
+impl Forkable {
+    fn mock<I: MockableArg, O: MockableRet>(mock: Mock<I, O>) -> Self {
+        Self {
+            value: Box::new(mock),
+            #[cfg(debug_assertions)]
+            fork: |mock, object_name| {
+                let mock: &Mock<I, O> = mock.downcast_ref().unwrap();
+                Box::new(mock.fork(object_name.clone()))
+            },
+        }
+    }
+
+    /// The output is shared by the forks, since a closure cannot be copied
+    #[cfg(debug_assertions)]
+    fn fallback_output<O: MockableRet>(output: SharedFallbackOutput<O>) -> Self {
+        Self {
+            value: Box::new(output),
+            fork: |output, _| {
+                let output: &SharedFallbackOutput<O> = output.downcast_ref().unwrap();
+                Box::new(output.clone())
+            },
+        }
+    }
+
+    #[cfg(debug_assertions)]
+    fn fork(&self, object_name: &ObjectName) -> Self {
+        Self {
+            value: (self.fork)(&self.value, object_name),
+            fork: self.fork,
+        }
+    }
+}
+
+/// Key of a mocked function that cannot be named with `std::any::Any::type_id`, like one with
+/// `impl Trait` arguments. `HASH` is of the path of the function, and `G` is its other generics.
+#[doc(hidden)]
+pub struct NamedKey<G: ?Sized, const HASH: u64>(PhantomData<G>);
+
+/// FNV-1a hash of the path of a function for `NamedKey`
+#[doc(hidden)]
+pub const fn named_key_hash(path: &str) -> u64 {
+    let bytes = path.as_bytes();
+    let mut hash = 0xcbf29ce484222325;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+        i += 1;
+    }
+    hash
+}
+
+type SharedFallbackOutput<O> = Arc<RuleBehavior<(), O>>;
+
+/// Output of a call found with the lock of the mocks
+pub(crate) enum CallOutput<I, O> {
+    Matched(MatchedCall<I, O>),
+    Fallback(Option<O>),
+    FallbackWith(SharedFallbackOutput<O>),
+}
+
+impl<I, O> CallOutput<I, O> {
+    /// Returns `None` if the real implementation should be called.
+    /// The lock of the mocks must be released before this, because a behavior may call the mocks.
+    #[track_caller]
+    pub(crate) fn output(self, receiver: Option<Receiver<'_>>) -> Option<O> {
+        match self {
+            CallOutput::Matched(call) => call.output(receiver),
+            CallOutput::Fallback(output) => output,
+            CallOutput::FallbackWith(output) => match output.call_without_input() {
+                Output::Found(output) => Some(output),
+                _ => unreachable!("`fallback_with` always returns the output"),
+            },
+        }
+    }
 }
 
 impl<I: MockableArg, O: MockableRet> MockGetter<I, O> for Mocks {
     fn get(&self, key: &TypeId, _name: &'static str) -> Option<&Mock<I, O>> {
         self.mock_objects
             .get(key)
-            .map(|mock| mock.downcast_ref().unwrap())
+            .map(|mock| mock.value.downcast_ref().unwrap())
     }
 
     fn get_mut_or_create(&mut self, key: TypeId, name: &'static str) -> &mut Mock<I, O> {
         self.mock_objects
             .entry(key)
-            .or_insert(Box::new(Mock::<I, O>::new(name)))
+            .or_insert_with(|| {
+                Forkable::mock(Mock::<I, O>::with_object_name(name, self.name.clone()))
+            })
+            .value
             .downcast_mut()
             .unwrap()
     }
 }
 
 impl Mocks {
+    /// Records the call and finds the output without calling the behavior of the matched rule.
+    /// Call [`CallOutput::output`] after releasing the lock of the mocks.
     #[track_caller]
-    #[doc(hidden)]
-    pub fn record_call_and_find_mock_output<I: MockableArg, O: MockableRet>(
+    pub(crate) fn record_call_and_find_rule<I: MockableArg, O: MockableRet>(
         &mut self,
         key: TypeId,
         name: &'static str,
         input: I,
-    ) -> Option<O> {
+        fallback: TypeFallback<O>,
+    ) -> CallOutput<I, O> {
         let mock = self.get_mut_or_create(key, name);
-        let result = mock.find_mock_output(&input);
-        mock.record_call(Arc::new(Mutex::new(input)));
-        result
+        match mock.record_call_and_find_rule(input) {
+            Some(call) => CallOutput::Matched(call),
+            None => self.fallback_output(name, fallback),
+        }
+    }
+
+    #[track_caller]
+    fn fallback_output<I, O: MockableRet>(
+        &mut self,
+        name: &'static str,
+        fallback: TypeFallback<O>,
+    ) -> CallOutput<I, O> {
+        if let Some(output) = self.fallback_outputs.get(&TypeId::of::<O>()) {
+            let output: &SharedFallbackOutput<O> = output.value.downcast_ref().unwrap();
+            // Like a rule, the closure running on this thread cannot be called again
+            if output.is_running() {
+                panic!(
+                    "fallback_with for {} is already running on this thread",
+                    self.name.describe(name)
+                );
+            }
+            return CallOutput::FallbackWith(output.clone());
+        }
+        CallOutput::Fallback(match self.fallback.or(fallback.policy).unwrap_or_default() {
+            Fallback::Panic => panic!("mock not found for {}", self.name.describe(name)),
+            Fallback::CallsRealImpl => None,
+            Fallback::Default => match fallback.default {
+                Some(default) => Some(default()),
+                None => panic!(
+                    "mock not found for {}, and the fallback cannot return `Default::default()` because `{}` does not implement `Default`. A generic output needs `#[mry::mry(fallback = \"default\")]` to find its `Default`",
+                    self.name.describe(name),
+                    std::any::type_name::<O>()
+                ),
+            },
+        })
+    }
+
+    #[cfg(debug_assertions)]
+    pub(crate) fn fallback_with<O: MockableRet>(
+        &mut self,
+        mut output: impl FnMut() -> O + Send + 'static,
+    ) {
+        let output: SharedFallbackOutput<O> =
+            Arc::new(RuleBehavior::new(crate::Behavior::Function {
+                clone: Clone::clone,
+                call: Box::new(move |()| output()),
+            }));
+        self.fallback_outputs
+            .insert(TypeId::of::<O>(), Forkable::fallback_output(output));
+    }
+
+    /// Copies the rules and the fallback into new mocks with no calls, for the object named `name`.
+    /// Behaviors of the rules and outputs of `fallback_with` are shared with these mocks.
+    #[cfg(debug_assertions)]
+    pub(crate) fn fork(&self, name: ObjectName) -> Self {
+        let fork = |objects: &HashMap<TypeId, Forkable>| {
+            objects
+                .iter()
+                .map(|(key, object)| (*key, object.fork(&name)))
+                .collect()
+        };
+        Self {
+            mock_objects: fork(&self.mock_objects),
+            fallback: self.fallback,
+            fallback_outputs: fork(&self.fallback_outputs),
+            name,
+        }
     }
 
     #[cfg(test)]
     pub(crate) fn insert<I: MockableArg, O: MockableRet>(&mut self, key: TypeId, item: Mock<I, O>) {
-        self.mock_objects.insert(key, Box::new(item));
+        self.mock_objects.insert(key, Forkable::mock(item));
     }
 
     pub(crate) fn remove(&mut self, key: &TypeId) -> Option<()> {
@@ -122,6 +280,124 @@ mod test {
         );
     }
 
+    #[test]
+    fn fallback_calls_real_impl_without_rule() {
+        let mut mock_data = Mocks {
+            fallback: Some(Fallback::CallsRealImpl),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            mock_data
+                .record_call_and_find_rule::<u8, u8>(
+                    TypeId::of::<usize>(),
+                    "meow",
+                    1,
+                    Default::default()
+                )
+                .output(None),
+            None
+        );
+        assert_eq!(
+            MockGetter::<u8, u8>::get(&mock_data, &TypeId::of::<usize>(), "meow")
+                .unwrap()
+                .log
+                .iter()
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "mock not found for meow")]
+    fn fallback_panics_by_default() {
+        let mut mock_data = Mocks::default();
+
+        mock_data
+            .record_call_and_find_rule::<u8, u8>(
+                TypeId::of::<usize>(),
+                "meow",
+                1,
+                crate::__fallback!(u8),
+            )
+            .output(None);
+    }
+
+    #[test]
+    fn fallback_returns_default() {
+        let mut mock_data = Mocks::default();
+
+        assert_eq!(
+            mock_data
+                .record_call_and_find_rule::<u8, u8>(
+                    TypeId::of::<usize>(),
+                    "meow",
+                    1,
+                    crate::__fallback!(u8, Some(Fallback::Default)),
+                )
+                .output(None),
+            Some(0)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "NotDefault` does not implement `Default`")]
+    fn fallback_panics_if_no_default() {
+        #[derive(Debug, PartialEq)]
+        struct NotDefault;
+        let mut mock_data = Mocks {
+            fallback: Some(Fallback::Default),
+            ..Default::default()
+        };
+
+        mock_data
+            .record_call_and_find_rule::<u8, NotDefault>(
+                TypeId::of::<usize>(),
+                "meow",
+                1,
+                crate::__fallback!(NotDefault),
+            )
+            .output(None);
+    }
+
+    #[test]
+    fn object_fallback_overrides_type_fallback() {
+        let mut mock_data = Mocks {
+            fallback: Some(Fallback::CallsRealImpl),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            mock_data
+                .record_call_and_find_rule::<u8, u8>(
+                    TypeId::of::<usize>(),
+                    "meow",
+                    1,
+                    crate::__fallback!(u8, Some(Fallback::Default)),
+                )
+                .output(None),
+            None
+        );
+    }
+
+    #[test]
+    fn fallback_with_returns_output_of_the_type() {
+        let mut mock_data = Mocks::default();
+        mock_data.fallback_with(|| Err::<u8, String>("not mocked".into()));
+
+        assert_eq!(
+            mock_data
+                .record_call_and_find_rule::<u8, Result<u8, String>>(
+                    TypeId::of::<usize>(),
+                    "meow",
+                    1,
+                    Default::default(),
+                )
+                .output(None),
+            Some(Err("not mocked".into()))
+        );
+    }
+
     #[test]
     // should not panic
     fn get_mut_or_create_returns_default() {
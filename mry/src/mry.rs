@@ -1,31 +1,46 @@
 use crate::mockable::MockableArg;
 use crate::mockable::MockableRet;
-#[cfg(debug_assertions)]
-use parking_lot::Mutex;
 use std::any::TypeId;
 use std::cmp::Ordering;
 #[cfg(debug_assertions)]
 use std::sync::atomic::AtomicU16;
 #[cfg(debug_assertions)]
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
+#[cfg(debug_assertions)]
+use crate::sync::Mutex;
+use crate::Fallback;
 #[cfg(debug_assertions)]
 use crate::MockGetter;
 #[cfg(debug_assertions)]
 use crate::Mocks;
+use crate::TypeFallback;
+#[cfg(debug_assertions)]
+use crate::{mock::ObjectName, mocks::CallOutput, Receiver};
 
 /// A unique id for an object
 pub type MryId = u16;
 #[cfg(debug_assertions)]
 static ID: AtomicU16 = AtomicU16::new(0);
 
-#[derive(Clone)]
-/// Mock container that has blank and harmless trait implementation for major traits such as `Eq` and `Ord`
+/// Mock container that has blank and harmless trait implementation for major traits such as `Eq` and `Ord`.
+///
+/// A clone made before the first `mock_*` call has independent mocks, and one made after shares
+/// them. A fallback given before that is copied into the clone. With a fallback, the first call
+/// also creates the mocks to record it. Use [`Mry::share`] to share them in either case, or [`Mry::fork`] to copy the rules.
 pub struct Mry {
     #[cfg(debug_assertions)]
     id: MryId,
+    /// The mocks are created on the first use, and shared by the containers from [`Mry::share`]
+    /// made before that
     #[cfg(debug_assertions)]
-    mocks: Option<Arc<Mutex<Mocks>>>,
+    mocks: Arc<OnceLock<Arc<Mutex<Mocks>>>>,
+    /// Kept out of the mocks, so that naming an object doesn't make it a mock
+    #[cfg(debug_assertions)]
+    name: ObjectName,
+    /// The fallback given before the mocks are created, which is copied into them on creation
+    #[cfg(debug_assertions)]
+    fallback: Arc<Mutex<Option<Fallback>>>,
 }
 
 #[test]
@@ -36,10 +51,40 @@ fn assert_send_mry() {
     assert_sync(Mry::default());
 }
 
+impl Clone for Mry {
+    #[cfg(debug_assertions)]
+    fn clone(&self) -> Self {
+        match self.mocks.get() {
+            Some(mocks) => Self {
+                id: self.id,
+                mocks: Arc::new(mocks.clone().into()),
+                name: self.name.clone(),
+                fallback: self.fallback.clone(),
+            },
+            None => Self {
+                id: self.id,
+                mocks: Default::default(),
+                name: self.name.fork(),
+                fallback: Arc::new(Mutex::new(*self.fallback.lock())),
+            },
+        }
+    }
+
+    #[cfg(not(debug_assertions))]
+    fn clone(&self) -> Self {
+        Self {}
+    }
+}
+
 impl std::fmt::Debug for Mry {
     #[cfg(debug_assertions)]
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Mry").field("id", &self.id).finish()
+        let mut debug = f.debug_struct("Mry");
+        debug.field("id", &self.id);
+        if let Some(name) = self.name() {
+            debug.field("name", &name);
+        }
+        debug.finish()
     }
     #[cfg(not(debug_assertions))]
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -49,9 +94,143 @@ impl std::fmt::Debug for Mry {
 
 impl Mry {
     #[cfg(debug_assertions)]
-    pub(crate) fn generate(&mut self) -> &mut Self {
-        self.mocks
-            .get_or_insert(Arc::new(Mutex::new(Default::default())));
+    pub(crate) fn generate(&self) -> &Arc<Mutex<Mocks>> {
+        self.mocks.get_or_init(|| {
+            Arc::new(Mutex::new(Mocks {
+                name: self.name.clone(),
+                fallback: *self.fallback.lock(),
+                ..Default::default()
+            }))
+        })
+    }
+
+    /// Returns a container sharing the rules, calls, and name with this one, even if no `mock_*`
+    /// has been called yet.
+    #[cfg(debug_assertions)]
+    pub fn share(&self) -> Self {
+        Self {
+            id: self.id,
+            mocks: self.mocks.clone(),
+            name: self.name.clone(),
+            fallback: self.fallback.clone(),
+        }
+    }
+
+    #[cfg(not(debug_assertions))]
+    pub fn share(&self) -> Self {
+        Self {}
+    }
+
+    /// Returns an independent container with copies of the rules, the fallback, and the name.
+    /// Calls are not copied. Behaviors of the rules, like the closure of `returns_with` or the
+    /// value of `returns_once`, are shared with this container, since they cannot be copied.
+    #[cfg(debug_assertions)]
+    pub fn fork(&self) -> Self {
+        let name = self.name.fork();
+        Self {
+            id: ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+            mocks: Arc::new(match self.mocks.get() {
+                Some(mocks) => Arc::new(Mutex::new(mocks.lock().fork(name.clone()))).into(),
+                None => OnceLock::new(),
+            }),
+            name,
+            fallback: Arc::new(Mutex::new(*self.fallback.lock())),
+        }
+    }
+
+    #[cfg(not(debug_assertions))]
+    pub fn fork(&self) -> Self {
+        Self {}
+    }
+
+    /// Creates a mock container with a name that appears in panic and assertion messages.
+    pub fn named(name: impl Into<String>) -> Self {
+        let mut mry = Self::default();
+        mry.set_name(name);
+        mry
+    }
+
+    /// Names the mock object. The name appears in panic and assertion messages like `Storage::get (primary)`.
+    #[cfg(debug_assertions)]
+    pub fn set_name(&mut self, name: impl Into<String>) -> &mut Self {
+        self.name.set(name.into());
+        self
+    }
+
+    #[cfg(not(debug_assertions))]
+    pub fn set_name(&mut self, _name: impl Into<String>) -> &mut Self {
+        self
+    }
+
+    /// Returns the name given by [`Mry::set_name`]
+    #[cfg(debug_assertions)]
+    pub fn name(&self) -> Option<String> {
+        self.name.get()
+    }
+
+    #[cfg(not(debug_assertions))]
+    pub fn name(&self) -> Option<String> {
+        None
+    }
+
+    /// Creates a mock container that calls the real implementation of every method with no matching rule, while recording the calls.
+    pub fn spy() -> Self {
+        Self::with_fallback(Fallback::CallsRealImpl)
+    }
+
+    /// Creates a mock container that returns `Default::default()` from every method with no matching rule.
+    pub fn nice() -> Self {
+        Self::with_fallback(Fallback::Default)
+    }
+
+    /// Creates a mock container with the given fallback for calls with no matching rule.
+    pub fn with_fallback(fallback: Fallback) -> Self {
+        let mut mry = Self::default();
+        mry.fallback(fallback);
+        mry
+    }
+
+    /// Makes every method with no matching rule call the real implementation, while recording the calls.
+    /// Rules added by `mock_*` still take precedence.
+    pub fn spy_all(&mut self) -> &mut Self {
+        self.fallback(Fallback::CallsRealImpl)
+    }
+
+    /// Sets what methods do when no rule matches a call.
+    /// This overrides the fallback given by `#[mry::mry(fallback = ..)]`.
+    #[cfg(debug_assertions)]
+    pub fn fallback(&mut self, fallback: Fallback) -> &mut Self {
+        // Locked first, so that mocks created meanwhile by a shared container copy it
+        let mut pending = self.fallback.lock();
+        match self.mocks.get() {
+            Some(mocks) => mocks.lock().fallback = Some(fallback),
+            None => *pending = Some(fallback),
+        }
+        drop(pending);
+        self
+    }
+
+    #[cfg(not(debug_assertions))]
+    pub fn fallback(&mut self, _fallback: Fallback) -> &mut Self {
+        self
+    }
+
+    /// Makes every method returning `O` with no matching rule return the output of the given function.
+    /// This takes precedence over [`Mry::fallback`].
+    #[cfg(debug_assertions)]
+    pub fn fallback_with<O: MockableRet>(
+        &mut self,
+        output: impl FnMut() -> O + Send + 'static,
+    ) -> &mut Self {
+        self.generate().lock().fallback_with(output);
+        self
+    }
+
+    #[cfg(not(debug_assertions))]
+    pub fn fallback_with<O: MockableRet>(
+        &mut self,
+        _output: impl FnMut() -> O + Send + 'static,
+    ) -> &mut Self {
         self
     }
 
@@ -63,14 +242,35 @@ impl Mry {
         key: TypeId,
         name: &'static str,
         input: I,
+        fallback: TypeFallback<O>,
     ) -> Option<O> {
-        if let Some(mocks) = self.mocks.as_ref() {
-            mocks
-                .lock()
-                .record_call_and_find_mock_output(key, name, input)
-        } else {
-            None
-        }
+        self.record_call(key, name, input, fallback).output()
+    }
+
+    /// Records the call and finds the output. The behavior is called by [`Call::output_with`]
+    /// after this borrow of the object ends, so that it can take the receiver.
+    #[doc(hidden)]
+    #[cfg(debug_assertions)]
+    #[track_caller]
+    pub fn record_call<I: MockableArg, O: MockableRet>(
+        &self,
+        key: TypeId,
+        name: &'static str,
+        input: I,
+        fallback: TypeFallback<O>,
+    ) -> Call<I, O> {
+        // Not in a closure, which would lose the location of `#[track_caller]`
+        let mocks = match self.mocks.get() {
+            Some(mocks) => mocks,
+            // The call is recorded, and the fallback may not be the real implementation
+            None if self.fallback.lock().is_some() => self.generate(),
+            None => return Call(None),
+        };
+        // Releases the lock before calling the behavior, which may call this object again.
+        let output = mocks
+            .lock()
+            .record_call_and_find_rule(key, name, input, fallback);
+        Call(Some(output))
     }
 
     #[cfg(not(debug_assertions))]
@@ -82,6 +282,7 @@ impl Mry {
         _key: TypeId,
         _name: &'static str,
         _input: I,
+        _fallback: TypeFallback<O>,
     ) -> Option<O> {
         None
     }
@@ -89,7 +290,27 @@ impl Mry {
     #[doc(hidden)]
     #[cfg(debug_assertions)]
     pub fn mocks<I: MockableArg, O: MockableRet>(&mut self) -> Arc<Mutex<dyn MockGetter<I, O>>> {
-        self.generate().mocks.as_ref().unwrap().clone()
+        self.generate().clone()
+    }
+}
+
+/// A recorded call of a mocked method, returned by [`Mry::record_call`]
+#[doc(hidden)]
+#[cfg(debug_assertions)]
+pub struct Call<I, O>(Option<CallOutput<I, O>>);
+
+#[cfg(debug_assertions)]
+impl<I, O> Call<I, O> {
+    /// Returns `None` if the real implementation should be called.
+    #[track_caller]
+    pub fn output(self) -> Option<O> {
+        self.0?.output(None)
+    }
+
+    /// Same as [`Call::output`], but lends the receiver to behaviors of `returns_with_self`
+    #[track_caller]
+    pub fn output_with<'a>(self, receiver: impl Into<Receiver<'a>>) -> Option<O> {
+        self.0?.output(Some(receiver.into()))
     }
 }
 
@@ -98,7 +319,9 @@ impl Default for Mry {
     fn default() -> Self {
         Self {
             id: ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
-            mocks: None,
+            mocks: Default::default(),
+            name: Default::default(),
+            fallback: Default::default(),
         }
     }
 
@@ -114,9 +337,7 @@ impl PartialOrd for Mry {
     }
 }
 
-impl Eq for Mry {
-    fn assert_receiver_is_total_eq(&self) {}
-}
+impl Eq for Mry {}
 
 impl Ord for Mry {
     fn cmp(&self, _: &Self) -> std::cmp::Ordering {
@@ -163,27 +384,29 @@ mod test {
 
     #[test]
     fn mry_unique() {
-        let mut mry1 = Mry::default();
+        let mry1 = Mry::default();
         let mry2 = Mry::default();
-        assert_ne!(mry1.generate().id, mry2.id);
+        mry1.generate();
+        assert_ne!(mry1.id, mry2.id);
     }
 
     #[test]
     fn mry_default_is_none() {
-        assert!(Mry::default().mocks.is_none());
+        assert!(Mry::default().mocks.get().is_none());
     }
 
     #[test]
     fn mry_always_equal() {
-        assert_eq!(*Mry::default().generate(), Mry::default());
+        let mry = Mry::default();
+        mry.generate();
+        assert_eq!(mry, Mry::default());
     }
 
     #[test]
     fn mry_always_equal_ord() {
-        assert_eq!(
-            Mry::default().cmp(Mry::default().generate()),
-            Ordering::Equal
-        );
+        let mry = Mry::default();
+        mry.generate();
+        assert_eq!(Mry::default().cmp(&mry), Ordering::Equal);
     }
 
     #[test]
@@ -205,36 +428,145 @@ mod test {
 
     #[test]
     fn generate_create_mock() {
-        let mut mry = Mry::default();
-        assert!(mry.mocks.is_none());
+        let mry = Mry::default();
+        assert!(mry.mocks.get().is_none());
         mry.generate();
-        assert!(mry.mocks.is_some());
+        assert!(mry.mocks.get().is_some());
     }
 
     #[test]
     fn generate_does_not_overwrite() {
-        let mut mry = Mry::default();
-        mry.generate();
-        mry.mocks
-            .as_ref()
-            .unwrap()
+        let mry = Mry::default();
+        mry.generate()
             .lock()
             .insert(TypeId::of::<usize>(), Mock::<usize, usize>::new(""));
-        mry.generate();
-        assert_eq!(mry.mocks.unwrap().lock().mock_objects.len(), 1);
+        assert_eq!(mry.generate().lock().mock_objects.len(), 1);
     }
 
     #[test]
     fn clone() {
-        let mut mry = Mry::default();
+        let mry = Mry::default();
+        mry.generate()
+            .lock()
+            .insert(TypeId::of::<usize>(), Mock::<usize, usize>::new(""));
+
+        assert_eq!(mry.clone().generate().lock().mock_objects.len(), 1);
+    }
+
+    #[test]
+    fn clone_before_generate_is_independent() {
+        let mry = Mry::named("primary");
+        let mut clone = mry.clone();
+        clone.set_name("clone");
+        mry.generate();
+
+        assert!(clone.mocks.get().is_none());
+        assert_eq!(mry.name(), Some("primary".to_string()));
+    }
+
+    #[test]
+    fn fallback_does_not_generate() {
+        let mry = Mry::nice();
+        let clone = mry.clone();
         mry.generate();
-        mry.mocks
-            .as_ref()
-            .unwrap()
+
+        assert!(clone.mocks.get().is_none());
+        assert_eq!(clone.generate().lock().fallback, Some(Fallback::Default));
+    }
+
+    #[test]
+    fn share_before_generate_shares_mocks() {
+        let mry = Mry::default();
+        let shared = mry.share();
+        mry.generate()
             .lock()
             .insert(TypeId::of::<usize>(), Mock::<usize, usize>::new(""));
 
-        assert_eq!(mry.clone().mocks.unwrap().lock().mock_objects.len(), 1);
+        assert_eq!(shared.generate().lock().mock_objects.len(), 1);
+    }
+
+    #[test]
+    fn fork_copies_mocks() {
+        let mry = Mry::named("primary");
+        mry.generate()
+            .lock()
+            .insert(TypeId::of::<usize>(), Mock::<usize, usize>::new(""));
+        let mut fork = mry.fork();
+        fork.set_name("fork");
+        fork.generate()
+            .lock()
+            .insert(TypeId::of::<u8>(), Mock::<u8, u8>::new(""));
+
+        assert_ne!(fork.id, mry.id);
+        assert_eq!(mry.generate().lock().mock_objects.len(), 1);
+        assert_eq!(fork.generate().lock().mock_objects.len(), 2);
+        assert_eq!(mry.name(), Some("primary".to_string()));
+    }
+
+    #[test]
+    fn fork_before_generate_is_independent() {
+        let mry = Mry::default();
+        let fork = mry.fork();
+        mry.generate();
+
+        assert!(fork.mocks.get().is_none());
+    }
+
+    #[test]
+    fn debug_shows_name() {
+        let mry = Mry::named("primary");
+        assert_eq!(
+            format!("{mry:?}"),
+            format!("Mry {{ id: {}, name: \"primary\" }}", mry.id)
+        );
+    }
+
+    #[test]
+    fn named_without_mocks_calls_real_impl() {
+        let mry = Mry::named("primary");
+
+        assert!(mry.mocks.get().is_none());
+        assert_eq!(
+            mry.record_call_and_find_mock_output::<u8, u8>(
+                TypeId::of::<usize>(),
+                "Storage::get",
+                1u8,
+                Default::default(),
+            ),
+            None
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "mock not found for Storage::get (primary)")]
+    fn name_appears_in_panic() {
+        let mry = Mry::named("primary");
+        mry.generate();
+        mry.record_call_and_find_mock_output::<u8, u8>(
+            TypeId::of::<usize>(),
+            "Storage::get",
+            1u8,
+            Default::default(),
+        );
+    }
+
+    #[test]
+    fn spy_all_records_calls() {
+        let mut mry = Mry::spy();
+
+        assert_eq!(
+            mry.record_call_and_find_mock_output::<u8, u8>(
+                TypeId::of::<usize>(),
+                "name",
+                1u8,
+                Default::default()
+            ),
+            None
+        );
+        mry.mocks::<u8, u8>()
+            .lock()
+            .get_mut_or_create(TypeId::of::<usize>(), "name")
+            .assert_called(&Matcher::new_eq(1u8), 1.into());
     }
 
     #[test]
@@ -242,7 +574,12 @@ mod test {
         let mry = Mry::default();
 
         assert_eq!(
-            mry.record_call_and_find_mock_output::<u8, u16>(TypeId::of::<usize>(), "name", 1u8),
+            mry.record_call_and_find_mock_output::<u8, u16>(
+                TypeId::of::<usize>(),
+                "name",
+                1u8,
+                Default::default()
+            ),
             None
         );
     }
@@ -257,7 +594,12 @@ mod test {
             .returns(Matcher::new_eq(1u8).wrapped(), 1u8);
 
         assert_eq!(
-            mry.record_call_and_find_mock_output::<u8, u8>(TypeId::of::<usize>(), "name", 1u8),
+            mry.record_call_and_find_mock_output::<u8, u8>(
+                TypeId::of::<usize>(),
+                "name",
+                1u8,
+                Default::default()
+            ),
             Some(1u8)
         );
     }
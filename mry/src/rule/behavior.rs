@@ -1,4 +1,4 @@
-use std::fmt::Debug;
+use std::{any::Any, fmt::Debug};
 
 use parking_lot::Mutex;
 
@@ -10,6 +10,10 @@ pub(crate) enum Output<O> {
     Found(O),
 }
 
+type ReceiverFn<I, O> = Box<dyn FnMut(Option<Receiver<'_>>, I) -> O + Send + 'static>;
+type SelfFn<S, I, O> = Box<dyn FnMut(&S, I) -> O + Send + 'static>;
+type SelfMutFn<S, I, O> = Box<dyn FnMut(&mut S, I) -> O + Send + 'static>;
+
 /// Behavior of mock
 pub enum Behavior<I, O> {
     /// Behaves with a function
@@ -17,6 +21,11 @@ pub enum Behavior<I, O> {
         clone: fn(&I) -> I,
         call: Box<dyn FnMut(I) -> O + Send + 'static>,
     },
+    /// Behaves with a function taking the receiver of the method
+    WithReceiver {
+        clone: fn(&I) -> I,
+        call: ReceiverFn<I, O>,
+    },
     /// Returns a constant value
     Const(Mutex<Box<dyn Iterator<Item = O> + Send + 'static>>),
     /// Once
@@ -29,6 +38,7 @@ impl<I: Debug, O: Debug> std::fmt::Debug for Behavior<I, O> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Function { .. } => f.debug_tuple("Function(_)").finish(),
+            Self::WithReceiver { .. } => f.debug_tuple("WithReceiver(_)").finish(),
             Self::Const(cons) => f
                 .debug_tuple("Const")
                 .field(&cons.lock().next().unwrap())
@@ -43,9 +53,16 @@ impl<I: Debug, O: Debug> std::fmt::Debug for Behavior<I, O> {
 }
 
 impl<I, O> Behavior<I, O> {
-    pub(crate) fn called(&mut self, input: &I) -> Output<O> {
+    pub(crate) fn called(&mut self, input: &Mutex<I>, receiver: Option<Receiver<'_>>) -> Output<O> {
         match self {
-            Behavior::Function { clone, call } => Output::Found(call(clone(input))),
+            Behavior::Function { clone, call } => {
+                let input = clone(&input.lock());
+                Output::Found(call(input))
+            }
+            Behavior::WithReceiver { clone, call } => {
+                let input = clone(&input.lock());
+                Output::Found(call(receiver, input))
+            }
             Behavior::Const(cons) => Output::Found(cons.get_mut().next().unwrap()),
             Behavior::Once(once) => {
                 if let Some(ret) = once.lock().take() {
@@ -59,6 +76,69 @@ impl<I, O> Behavior<I, O> {
     }
 }
 
+/// The receiver of a mocked method lent to the behaviors of `returns_with_self`
+pub enum Receiver<'a> {
+    Ref(&'a dyn Any),
+    Mut(&'a mut dyn Any),
+}
+
+impl<'a, S: Any> From<&'a S> for Receiver<'a> {
+    fn from(receiver: &'a S) -> Self {
+        Receiver::Ref(receiver)
+    }
+}
+
+impl<'a, S: Any> From<&'a mut S> for Receiver<'a> {
+    fn from(receiver: &'a mut S) -> Self {
+        Receiver::Mut(receiver)
+    }
+}
+
+impl<'a> Receiver<'a> {
+    #[track_caller]
+    pub(crate) fn get<S: Any>(receiver: Option<Self>, name: &str) -> &'a S {
+        let receiver = match receiver {
+            Some(Receiver::Ref(receiver)) => receiver.downcast_ref(),
+            Some(Receiver::Mut(receiver)) => receiver.downcast_ref(),
+            None => panic!("`returns_with_self` is used for {name}, which has no receiver"),
+        };
+        receiver.unwrap_or_else(|| {
+            panic!(
+                "`returns_with_self` for {name} takes `&{}`, which is not the receiver",
+                std::any::type_name::<S>()
+            )
+        })
+    }
+
+    #[track_caller]
+    pub(crate) fn get_mut<S: Any>(receiver: Option<Self>, name: &str) -> &'a mut S {
+        let receiver = match receiver {
+            Some(Receiver::Mut(receiver)) => receiver.downcast_mut(),
+            _ => panic!(
+                "`returns_with_self_mut` is used for {name}, which has no `&mut self` receiver"
+            ),
+        };
+        receiver.unwrap_or_else(|| {
+            panic!(
+                "`returns_with_self_mut` for {name} takes `&mut {}`, which is not the receiver",
+                std::any::type_name::<S>()
+            )
+        })
+    }
+}
+
+/// A behavior of `returns_with_self` that takes `&S` and the arguments
+#[doc(hidden)]
+pub struct WithSelf<S, I, O>(pub(crate) SelfFn<S, I, O>);
+
+/// A behavior of `returns_with_self_mut` that takes `&mut S` and the arguments
+#[doc(hidden)]
+pub struct WithSelfMut<S, I, O>(pub(crate) SelfMutFn<S, I, O>);
+
+/// A closure taking the arguments like the one of `returns_with`, but returning any type
+#[doc(hidden)]
+pub struct WithArgs<I, O>(pub(crate) Box<dyn FnMut(I) -> O + Send + 'static>);
+
 mry_macros::create_behaviors!();
 
 #[cfg(test)]
@@ -80,15 +160,39 @@ mod tests {
                 call: Box::new(|()| "aaa"),
                 clone: Clone::clone
             }
-            .called(&()),
+            .called(&Mutex::new(()), None),
             Output::Found("aaa")
         );
     }
 
+    #[test]
+    fn with_receiver() {
+        struct Cat(&'static str);
+        let mut behavior = Behavior::WithReceiver {
+            clone: Clone::clone,
+            call: Box::new(|receiver, count: usize| {
+                Receiver::get::<Cat>(receiver, "Cat::meow").0.repeat(count)
+            }),
+        };
+
+        assert_eq!(
+            behavior.called(&Mutex::new(2), Some((&Cat("meow")).into())),
+            Output::Found("meowmeow".to_string())
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "`returns_with_self_mut` is used for Cat::meow, which has no `&mut self` receiver"
+    )]
+    fn get_mut_from_shared_receiver() {
+        Receiver::get_mut::<u8>(Some((&1u8).into()), "Cat::meow");
+    }
+
     #[test]
     fn const_value() {
         assert_eq!(
-            Behavior::Const(Mutex::new(Box::new(repeat("aaa")))).called(&()),
+            Behavior::Const(Mutex::new(Box::new(repeat("aaa")))).called(&Mutex::new(()), None),
             Output::Found("aaa")
         );
     }
@@ -96,7 +200,7 @@ mod tests {
     #[test]
     fn calls_real_impl() {
         assert_eq!(
-            Behavior::<_, ()>::CallsRealImpl.called(&()),
+            Behavior::<_, ()>::CallsRealImpl.called(&Mutex::new(()), None),
             Output::CallsRealImpl
         );
     }
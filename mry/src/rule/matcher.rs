@@ -17,6 +17,11 @@ impl<I> Matcher<I> {
     pub(crate) fn matches(&self, input: &I) -> bool {
         self.0.matches(input)
     }
+
+    /// Describes the arguments like `(var > 3, _)` if any of them has a description
+    pub(crate) fn describe(&self) -> Option<String> {
+        self.0.describe()
+    }
 }
 
 #[cfg(test)]
@@ -48,6 +53,10 @@ impl<I> Matcher<I> {
 
 pub trait Match<I> {
     fn matches(&self, input: &I) -> bool;
+
+    fn describe(&self) -> Option<String> {
+        None
+    }
 }
 
 pub enum ArgMatcher<I> {
@@ -58,6 +67,11 @@ pub enum ArgMatcher<I> {
     },
     Any,
     Never,
+    /// A matcher with a description shown in assertion messages
+    Described {
+        matcher: Box<ArgMatcher<I>>,
+        description: String,
+    },
 }
 
 impl<I> ArgMatcher<I> {
@@ -74,6 +88,22 @@ impl<I> ArgMatcher<I> {
             ArgMatcher::Eq { value, partial_eq } => partial_eq(value, input),
             ArgMatcher::Any => true,
             ArgMatcher::Never => false,
+            ArgMatcher::Described { matcher, .. } => matcher.matches(input),
+        }
+    }
+
+    /// Adds a description like `is even` shown in the message of a failed `assert_called`
+    pub fn described(self, description: impl Into<String>) -> Self {
+        ArgMatcher::Described {
+            matcher: Box::new(self),
+            description: description.into(),
+        }
+    }
+
+    pub(crate) fn description(&self) -> Option<&str> {
+        match self {
+            ArgMatcher::Described { description, .. } => Some(description),
+            _ => None,
         }
     }
 }
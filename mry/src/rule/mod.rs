@@ -2,6 +2,7 @@ mod behavior;
 mod matcher;
 
 use std::sync::Arc;
+use std::thread::ThreadId;
 
 pub use behavior::*;
 pub use matcher::*;
@@ -9,14 +10,101 @@ use parking_lot::Mutex;
 
 pub(crate) struct Rule<I, O> {
     pub matcher: Arc<Mutex<Matcher<I>>>,
-    pub behavior: Behavior<I, O>,
+    pub behavior: Arc<RuleBehavior<I, O>>,
+}
+
+// The matcher and the behavior are shared by the rules copied by `Mry::fork`.
+impl<I, O> Clone for Rule<I, O> {
+    fn clone(&self) -> Self {
+        Self {
+            matcher: self.matcher.clone(),
+            behavior: self.behavior.clone(),
+        }
+    }
 }
 
 impl<I, O> Rule<I, O> {
+    pub fn new(matcher: Arc<Mutex<Matcher<I>>>, behavior: Behavior<I, O>) -> Self {
+        Self {
+            matcher,
+            behavior: Arc::new(RuleBehavior::new(behavior)),
+        }
+    }
+
     pub fn matches(&self, input: &I) -> bool {
         self.matcher.lock().matches(input)
     }
-    pub fn call_behavior(&mut self, input: &I) -> Output<O> {
-        self.behavior.called(input)
+}
+
+/// Behavior of a rule, called after the lock of the mocks is released
+pub(crate) struct RuleBehavior<I, O> {
+    behavior: Mutex<Behavior<I, O>>,
+    /// The thread calling the behavior
+    running: Mutex<Option<ThreadId>>,
+}
+
+impl<I, O> RuleBehavior<I, O> {
+    pub fn new(behavior: Behavior<I, O>) -> Self {
+        Self {
+            behavior: Mutex::new(behavior),
+            running: Mutex::new(None),
+        }
+    }
+
+    /// Whether the behavior is running on the current thread
+    pub fn is_running(&self) -> bool {
+        *self.running.lock() == Some(std::thread::current().id())
+    }
+
+    /// Calls the behavior, which must not be running on the current thread.
+    /// Calls from other threads wait for it, because a `FnMut` cannot run concurrently.
+    pub fn call(&self, input: &Mutex<I>, receiver: Option<Receiver<'_>>) -> Output<O> {
+        let mut behavior = self.behavior.lock();
+        *self.running.lock() = Some(std::thread::current().id());
+        let _running = Running(&self.running);
+        behavior.called(input, receiver)
+    }
+}
+
+impl<O> RuleBehavior<(), O> {
+    /// Calls a behavior with no input, like the closure of `fallback_with`
+    pub(crate) fn call_without_input(&self) -> Output<O> {
+        self.call(&Mutex::new(()), None)
+    }
+}
+
+struct Running<'a>(&'a Mutex<Option<ThreadId>>);
+
+impl Drop for Running<'_> {
+    fn drop(&mut self) {
+        *self.0.lock() = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Slot = Arc<Mutex<Option<Arc<RuleBehavior<(), bool>>>>>;
+
+    #[test]
+    fn running_on_the_current_thread() {
+        let slot = Slot::default();
+        let inner = slot.clone();
+        let rule = Rule::new(
+            Arc::new(Mutex::new(Matcher::any())),
+            Behavior::Function {
+                clone: Clone::clone,
+                call: Box::new(move |()| inner.lock().as_ref().unwrap().is_running()),
+            },
+        );
+        *slot.lock() = Some(rule.behavior.clone());
+
+        assert!(!rule.behavior.is_running());
+        assert_eq!(
+            rule.behavior.call(&Mutex::new(()), None),
+            Output::Found(true)
+        );
+        assert!(!rule.behavior.is_running());
     }
 }
@@ -1,27 +1,39 @@
 use crate::{
     mock::Mock,
     mockable::{MockableArg, MockableRet},
-    MockGetter, Mocks,
+    mocks::CallOutput,
+    MockGetter, Mocks, TypeFallback,
 };
+mod context;
+mod lock;
+
 use async_recursion::async_recursion;
+pub use context::{with_static_mocks, StaticMocksScope};
+pub use lock::{StaticLock, StaticLockGuard, LOCK_TIMEOUT_ENV};
 use parking_lot::Mutex;
-use std::{
-    any::TypeId, cell::RefCell, collections::HashMap, future::Future, ops::Deref, pin::Pin,
-    sync::Arc,
-};
+use std::{any::TypeId, cell::Cell, collections::BTreeMap, future::Future, pin::Pin, sync::Arc};
 
-// This cannot be `Rc<RefCell<>>` because `MockLocator` expects `Arc<Mutex<>>`.
-thread_local! {
-    pub static STATIC_MOCKS: Arc<Mutex<StaticMocks>> = Arc::new(Mutex::new(StaticMocks::default()));
+// Locks are shared by all threads to serialize tests that mock the same function.
+#[doc(hidden)]
+pub static STATIC_MOCK_LOCKS: Mutex<BTreeMap<TypeId, Arc<StaticLock>>> =
+    Mutex::new(BTreeMap::new());
+
+#[doc(hidden)]
+pub fn get_static_mocks() -> Arc<crate::sync::Mutex<StaticMocks>> {
+    context::current().mocks
 }
 
 thread_local! {
-    pub static STATIC_MOCK_LOCKS: RefCell<HashMap<TypeId, Arc<Mutex<()>>>> = RefCell::new(HashMap::new());
+    static FINDING: Cell<bool> = const { Cell::new(false) };
 }
 
-#[doc(hidden)]
-pub fn get_static_mocks() -> Arc<Mutex<StaticMocks>> {
-    STATIC_MOCKS.with(Clone::clone)
+/// Clears `FINDING` even if a matcher panics
+struct Finding;
+
+impl Drop for Finding {
+    fn drop(&mut self) {
+        FINDING.set(false);
+    }
 }
 
 #[doc(hidden)]
@@ -30,36 +42,41 @@ pub fn static_record_call_and_find_mock_output<I: MockableArg, O: MockableRet>(
     key: TypeId,
     name: &'static str,
     input: I,
+    fallback: TypeFallback<O>,
 ) -> Option<O> {
-    STATIC_MOCKS.with(|mocks| {
-        if let Some(mut lock) = mocks.try_lock() {
-            lock.record_call_and_find_mock_output(key, name, input)
-        } else {
-            // In single threaded scenario, this branch means that a mock behavior recursively calls the target function.
-            // In that case, user would intent partial mocking.
-            // So we return None to avoid panic.
-            None
-        }
-    })
+    // Behaviors are called after releasing the lock, so only a matcher can reach here, which
+    // calls the real implementation. Other threads sharing the mocks may hold the lock, so this is
+    // tracked per thread.
+    if FINDING.replace(true) {
+        return None;
+    }
+    let _finding = Finding;
+    let mocks = get_static_mocks();
+    let output = mocks
+        .lock()
+        .record_call_and_find_rule(key, name, input, fallback);
+    drop(_finding);
+    // Releases the lock before calling the behavior, which may call this function again.
+    output?.output(None)
 }
 
 #[doc(hidden)]
 pub struct StaticMockMutex {
     pub key: TypeId,
     pub name: String,
-    pub mutex: Arc<Mutex<()>>,
+    pub mutex: Arc<StaticLock>,
 }
 
 #[doc(hidden)]
 pub struct StaticMockLock<'a> {
     pub key: TypeId,
     pub name: String,
-    pub lock: Box<dyn Deref<Target = ()> + 'a>,
+    pub lock: Option<StaticLockGuard<'a>>,
 }
 impl StaticMockLock<'_> {
     #[track_caller]
     fn release(&self) {
-        let mocks = STATIC_MOCKS.with(Clone::clone);
+        let mocks = get_static_mocks();
         if mocks.lock().0.remove(&self.key).is_none() {
             panic!(
                 "{} is locked but no used. Remove {} from mry::lock",
@@ -74,13 +91,10 @@ impl StaticMockLock<'_> {
 pub struct StaticMocks(Mocks);
 
 fn check_locked(key: &TypeId) -> bool {
-    STATIC_MOCK_LOCKS.with(|locks| {
-        locks
-            .borrow()
-            .get(key)
-            .map(|lock| lock.try_lock().is_none())
-            .unwrap_or(false)
-    })
+    STATIC_MOCK_LOCKS
+        .lock()
+        .get(key)
+        .is_some_and(|lock| lock.is_held_by_current_context())
 }
 
 impl<I: MockableArg, O: MockableRet> MockGetter<I, O> for StaticMocks {
@@ -102,15 +116,17 @@ impl<I: MockableArg, O: MockableRet> MockGetter<I, O> for StaticMocks {
 }
 
 impl StaticMocks {
+    /// Returns `None` if the lock of the function is not acquired
     #[track_caller]
-    pub fn record_call_and_find_mock_output<I: MockableArg, O: MockableRet>(
+    pub(crate) fn record_call_and_find_rule<I: MockableArg, O: MockableRet>(
         &mut self,
         key: TypeId,
         name: &'static str,
         input: I,
-    ) -> Option<O> {
+        fallback: TypeFallback<O>,
+    ) -> Option<CallOutput<I, O>> {
         if check_locked(&key) {
-            self.0.record_call_and_find_mock_output(key, name, input)
+            Some(self.0.record_call_and_find_rule(key, name, input, fallback))
         } else {
             None
         }
@@ -121,17 +137,17 @@ impl StaticMocks {
 pub fn __mutexes(mut keys: Vec<(TypeId, String)>) -> Vec<StaticMockMutex> {
     // Prevent deadlock by sorting the keys.
     keys.sort();
+    // Locking the same mutex twice also deadlocks.
+    keys.dedup_by_key(|(key, _)| *key);
     keys.into_iter()
         .map(|(key, name)| StaticMockMutex {
             key,
             name,
-            mutex: STATIC_MOCK_LOCKS.with(|locks| {
-                locks
-                    .borrow_mut()
-                    .entry(key)
-                    .or_insert(Arc::new(Default::default()))
-                    .clone()
-            }),
+            mutex: STATIC_MOCK_LOCKS
+                .lock()
+                .entry(key)
+                .or_insert(Arc::new(Default::default()))
+                .clone(),
         })
         .collect()
 }
@@ -142,8 +158,8 @@ pub fn __lock_and_run<T>(mut mutexes: Vec<StaticMockMutex>, function: fn() -> T)
     if let Some(mutex) = mutexes.pop() {
         let lock = StaticMockLock {
             key: mutex.key,
+            lock: Some(mutex.mutex.lock(&mutex.name, lock::lock_timeout())),
             name: mutex.name,
-            lock: Box::new(mutex.mutex.lock()),
         };
         let result = __lock_and_run(mutexes, function);
         lock.release();
@@ -154,18 +170,35 @@ pub fn __lock_and_run<T>(mut mutexes: Vec<StaticMockMutex>, function: fn() -> T)
 }
 
 #[doc(hidden)]
-#[async_recursion(?Send)]
 pub async fn __async_lock_and_run<T>(
+    mutexes: Vec<StaticMockMutex>,
+    function: fn() -> Pin<Box<dyn Future<Output = T>>>,
+) -> T {
+    // The test may be resumed on another thread, so the mocks and locks follow the future instead of the thread.
+    StaticMocksScope::new(
+        context::Context::new(),
+        async_lock_and_run(mutexes, function),
+    )
+    .await
+}
+
+#[async_recursion(?Send)]
+async fn async_lock_and_run<T>(
     mut mutexes: Vec<StaticMockMutex>,
     function: fn() -> Pin<Box<dyn Future<Output = T>>>,
 ) -> T {
     if let Some(mutex) = mutexes.pop() {
+        // Waits without blocking the thread, which may run the test holding the lock
+        let lock = mutex
+            .mutex
+            .lock_async(&mutex.name, lock::lock_timeout())
+            .await;
         let _lock = StaticMockLock {
             key: mutex.key,
+            lock: Some(lock),
             name: mutex.name,
-            lock: Box::new(mutex.mutex.lock()),
         };
-        __async_lock_and_run(mutexes, function).await
+        async_lock_and_run(mutexes, function).await
     } else {
         function().await
     }
@@ -186,16 +219,15 @@ mod tests {
             Arc::new(Default::default()),
         );
 
-        assert_eq!(
-            STATIC_MOCKS.with(
-                |mocks| mocks.lock().record_call_and_find_mock_output::<(), ()>(
-                    returns_none_if_not_mocked.type_id(),
-                    "meow",
-                    ()
-                )
-            ),
-            None
-        );
+        assert!(get_static_mocks()
+            .lock()
+            .record_call_and_find_rule::<(), ()>(
+                returns_none_if_not_mocked.type_id(),
+                "meow",
+                (),
+                Default::default(),
+            )
+            .is_none());
     }
 
     #[test]
@@ -206,17 +238,21 @@ mod tests {
             .returns(Matcher::new_eq(()).wrapped(), ());
         let mut static_mocks = StaticMocks(mocks);
 
-        let mutex = Arc::new(Mutex::default());
-        let _lock = mutex.lock();
+        let mutex = Arc::new(StaticLock::default());
+        let _lock = mutex.lock("meow", lock::lock_timeout());
 
         insert_lock(returns_some_if_mocked.type_id(), mutex.clone());
 
         assert_eq!(
-            static_mocks.record_call_and_find_mock_output::<(), ()>(
-                returns_some_if_mocked.type_id(),
-                "meow",
-                ()
-            ),
+            static_mocks
+                .record_call_and_find_rule::<(), ()>(
+                    returns_some_if_mocked.type_id(),
+                    "meow",
+                    (),
+                    Default::default(),
+                )
+                .unwrap()
+                .output(None),
             Some(())
         );
     }
@@ -224,7 +260,7 @@ mod tests {
     #[test]
     #[should_panic(expected = "the lock of `meow` is not acquired.")]
     fn panic_if_lock_is_not_created() {
-        let mocks = STATIC_MOCKS.with(Clone::clone);
+        let mocks = get_static_mocks();
         MockGetter::<(), ()>::get(
             &mocks.lock(),
             &panic_if_lock_is_not_created.type_id(),
@@ -235,7 +271,7 @@ mod tests {
     #[test]
     #[should_panic(expected = "the lock of `meow` is not acquired.")]
     fn panic_if_lock_is_not_created_mut() {
-        let mocks = STATIC_MOCKS.with(Clone::clone);
+        let mocks = get_static_mocks();
         MockGetter::<(), ()>::get_mut_or_create(
             &mut mocks.lock(),
             panic_if_lock_is_not_created_mut.type_id(),
@@ -250,7 +286,7 @@ mod tests {
             panic_if_lock_is_not_acquired.type_id(),
             Arc::new(Default::default()),
         );
-        let mocks = STATIC_MOCKS.with(Clone::clone);
+        let mocks = get_static_mocks();
         MockGetter::<(), ()>::get(
             &mocks.lock(),
             &panic_if_lock_is_not_acquired.type_id(),
@@ -265,7 +301,7 @@ mod tests {
             panic_if_lock_is_not_acquired_mut.type_id(),
             Arc::new(Default::default()),
         );
-        let mocks = STATIC_MOCKS.with(Clone::clone);
+        let mocks = get_static_mocks();
         MockGetter::<(), ()>::get_mut_or_create(
             &mut mocks.lock(),
             panic_if_lock_is_not_acquired_mut.type_id(),
@@ -283,11 +319,11 @@ mod tests {
         StaticMockLock {
             key: delete_mock_when_lock_is_dropped.type_id(),
             name: "name".to_string(),
-            lock: Box::new(Box::new(())),
+            lock: None,
         }
         .release();
 
-        let mocks = STATIC_MOCKS.with(Clone::clone);
+        let mocks = get_static_mocks();
         assert!(MockGetter::<usize, usize>::get(
             &mocks.lock().0,
             &delete_mock_when_lock_is_dropped.type_id(),
@@ -316,14 +352,14 @@ mod tests {
             "name".to_string(),
         )]);
 
-        let _lock = mutexes[0].mutex.try_lock().unwrap();
+        let _lock = mutexes[0].mutex.lock("name", lock::lock_timeout());
 
         let mutexes = __mutexes(vec![(
             __mutexes_does_not_overwrite_mutexes.type_id(),
             "name".to_string(),
         )]);
 
-        assert!(mutexes[0].mutex.try_lock().is_none());
+        assert!(mutexes[0].mutex.is_held_by_current_context());
 
         cleanup_static_mock_lock(__mutexes_creates_mutexes.type_id());
     }
@@ -341,6 +377,29 @@ mod tests {
         assert_eq!(mutexes.iter().map(|m| m.key).collect::<Vec<_>>(), keys);
     }
 
+    #[test]
+    fn __mutexes_deduplicates_keys() {
+        let mutexes = __mutexes(vec![
+            (0u8.type_id(), "a".to_string()),
+            (0u16.type_id(), "b".to_string()),
+            (0u8.type_id(), "c".to_string()),
+        ]);
+
+        assert_eq!(mutexes.len(), 2);
+    }
+
+    #[test]
+    fn __lock_and_run_is_not_locked_in_other_threads() {
+        fn a() {}
+
+        __lock_and_run(__mutexes(vec![(a.type_id(), "a".into())]), || {
+            insert_mock(a.type_id(), Mock::<usize, usize>::new(""));
+            assert!(!std::thread::spawn(|| check_locked(&a.type_id()))
+                .join()
+                .unwrap());
+        });
+    }
+
     #[test]
     fn __lock_and_run_just_runs() {
         assert_eq!(__lock_and_run(vec![], || 42), 42)
@@ -356,9 +415,9 @@ mod tests {
 
         let mutexes = __mutexes(vec![(a.type_id(), "a".into()), (b.type_id(), "b".into())]);
         __lock_and_run(mutexes, || {
-            assert!(get_lock(a.type_id()).unwrap().try_lock().is_none());
+            assert!(get_lock(a.type_id()).unwrap().is_held_by_current_context());
 
-            assert!(get_lock(b.type_id()).unwrap().try_lock().is_none());
+            assert!(get_lock(b.type_id()).unwrap().is_held_by_current_context());
         });
     }
 
@@ -373,7 +432,7 @@ mod tests {
         __lock_and_run(
             __mutexes(vec![(a.type_id(), "a".into()), (b.type_id(), "b".into())]),
             || {
-                let mocks = STATIC_MOCKS.with(Clone::clone);
+                let mocks = get_static_mocks();
                 assert!(
                     MockGetter::<usize, usize>::get(&mocks.lock(), &a.type_id(), "a").is_some()
                 );
@@ -384,7 +443,7 @@ mod tests {
             },
         );
 
-        let mocks = STATIC_MOCKS.with(Clone::clone);
+        let mocks = get_static_mocks();
 
         assert!(MockGetter::<usize, usize>::get(&mocks.lock().0, &a.type_id(), "a").is_none());
 
@@ -392,20 +451,18 @@ mod tests {
     }
 
     fn insert_mock<I: MockableArg, O: MockableRet>(key: TypeId, mock: Mock<I, O>) {
-        STATIC_MOCKS.with(|mocks| mocks.lock().0.insert(key, mock));
+        get_static_mocks().lock().0.insert(key, mock);
     }
 
-    fn insert_lock(key: TypeId, lock: Arc<Mutex<()>>) {
-        STATIC_MOCK_LOCKS.with(|locks| {
-            locks.borrow_mut().insert(key, lock);
-        });
+    fn insert_lock(key: TypeId, lock: Arc<StaticLock>) {
+        STATIC_MOCK_LOCKS.lock().insert(key, lock);
     }
 
-    fn get_lock(key: TypeId) -> Option<Arc<Mutex<()>>> {
-        STATIC_MOCK_LOCKS.with(|locks| locks.borrow().get(&key).cloned())
+    fn get_lock(key: TypeId) -> Option<Arc<StaticLock>> {
+        STATIC_MOCK_LOCKS.lock().get(&key).cloned()
     }
 
     fn cleanup_static_mock_lock(key: TypeId) {
-        STATIC_MOCK_LOCKS.with(|locks| locks.borrow_mut().remove(&key));
+        STATIC_MOCK_LOCKS.lock().remove(&key);
     }
 }
@@ -0,0 +1,158 @@
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::Poll,
+};
+
+use super::StaticMocks;
+use crate::sync::Mutex;
+
+/// Identifies who owns static mocks and their locks: a thread, or an async test wherever it is polled
+pub(crate) type ContextId = u64;
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Clone)]
+pub(crate) struct Context {
+    pub id: ContextId,
+    pub mocks: Arc<Mutex<StaticMocks>>,
+}
+
+impl Context {
+    pub(crate) fn new() -> Self {
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            mocks: Arc::new(Mutex::unmodeled(Default::default())),
+        }
+    }
+}
+
+// Each thread has its own context until a `StaticMocksScope` is polled on it.
+thread_local! {
+    static CURRENT: RefCell<Context> = RefCell::new(Context::new());
+}
+
+pub(crate) fn current() -> Context {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+pub(crate) fn current_id() -> ContextId {
+    CURRENT.with(|current| current.borrow().id)
+}
+
+/// Future that is polled with the static mocks and locks of a test, on whichever thread it runs.
+/// Created by [`with_static_mocks`].
+pub struct StaticMocksScope<F> {
+    context: Context,
+    future: Pin<Box<F>>,
+}
+
+impl<F> StaticMocksScope<F> {
+    pub(crate) fn new(context: Context, future: F) -> Self {
+        Self {
+            context,
+            future: Box::pin(future),
+        }
+    }
+}
+
+impl<F: Future> Future for StaticMocksScope<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let _restore = Restore(Some(
+            CURRENT.with(|current| current.replace(this.context.clone())),
+        ));
+        this.future.as_mut().poll(cx)
+    }
+}
+
+/// Restores the context of the thread even if the future panics
+struct Restore(Option<Context>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        if let Some(previous) = self.0.take() {
+            CURRENT.with(|current| current.replace(previous));
+        }
+    }
+}
+
+/// Runs the future with the static mocks and locks of the current test.
+/// Wrap a future spawned by a test so that it sees the mocks on any thread, like
+/// `tokio::spawn(mry::with_static_mocks(async { .. }))`.
+pub fn with_static_mocks<F: Future>(future: F) -> StaticMocksScope<F> {
+    StaticMocksScope::new(current(), future)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::task::Waker;
+
+    use super::*;
+
+    fn poll<F: Future>(future: &mut StaticMocksScope<F>) -> Poll<F::Output> {
+        Pin::new(future).poll(&mut std::task::Context::from_waker(Waker::noop()))
+    }
+
+    #[test]
+    fn context_follows_future_across_threads() {
+        let context = Context::new();
+        let id = context.id;
+        let mut future = StaticMocksScope::new(context, async {
+            let first = current_id();
+            Yield(false).await;
+            (first, current_id())
+        });
+
+        assert!(poll(&mut future).is_pending());
+        let result = std::thread::spawn(move || poll(&mut future))
+            .join()
+            .unwrap();
+
+        assert_eq!(result, Poll::Ready((id, id)));
+    }
+
+    #[test]
+    fn restores_thread_context() {
+        let id = current_id();
+        let mut future = StaticMocksScope::new(Context::new(), async { current_id() });
+
+        assert!(matches!(poll(&mut future), Poll::Ready(inner) if inner != id));
+        assert_eq!(current_id(), id);
+    }
+
+    #[test]
+    fn with_static_mocks_shares_current_context() {
+        let id = current_id();
+        let mut future = with_static_mocks(async { current_id() });
+
+        assert_eq!(
+            std::thread::spawn(move || poll(&mut future))
+                .join()
+                .unwrap(),
+            Poll::Ready(id)
+        );
+    }
+
+    struct Yield(bool);
+
+    impl Future for Yield {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, _: &mut std::task::Context<'_>) -> Poll<()> {
+            if self.0 {
+                Poll::Ready(())
+            } else {
+                self.0 = true;
+                Poll::Pending
+            }
+        }
+    }
+}
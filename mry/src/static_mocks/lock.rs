@@ -0,0 +1,429 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        OnceLock,
+    },
+    task::{Poll, Waker},
+    thread,
+    time::{Duration, Instant},
+};
+
+use parking_lot::{Condvar, Mutex};
+
+use super::context::{self, ContextId};
+
+/// Environment variable to override how long `#[mry::lock]` waits for a lock in seconds
+pub const LOCK_TIMEOUT_ENV: &str = "MRY_LOCK_TIMEOUT";
+
+const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub(crate) fn lock_timeout() -> Duration {
+    std::env::var(LOCK_TIMEOUT_ENV)
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_LOCK_TIMEOUT)
+}
+
+struct Holder {
+    context: ContextId,
+    test: String,
+}
+
+impl Holder {
+    fn current() -> Self {
+        Self {
+            context: context::current_id(),
+            test: thread::current().name().unwrap_or("<unnamed>").to_string(),
+        }
+    }
+}
+
+/// A process-global lock of a static mock which remembers the test holding it
+#[doc(hidden)]
+#[derive(Default)]
+pub struct StaticLock {
+    holder: Mutex<Option<Holder>>,
+    released: Condvar,
+    /// Async tests waiting for the lock, keyed by the id of the waiter
+    wakers: Mutex<HashMap<u64, Waker>>,
+}
+
+impl StaticLock {
+    #[track_caller]
+    pub(crate) fn lock(&self, name: &str, timeout: Duration) -> StaticLockGuard<'_> {
+        let current = context::current_id();
+        let deadline = Instant::now() + timeout;
+        let mut holder = self.holder.lock();
+        while let Some(Holder { context, test }) = &*holder {
+            if *context == current {
+                panic!("the lock of `{name}` is already acquired by `{test}`");
+            }
+            if self.released.wait_until(&mut holder, deadline).timed_out() {
+                if let Some(Holder { test, .. }) = &*holder {
+                    panic!(
+                        "timed out after {timeout:?} waiting for the lock of `{name}` held by `{test}`. Set {LOCK_TIMEOUT_ENV} to wait longer"
+                    );
+                }
+            }
+        }
+        *holder = Some(Holder::current());
+        StaticLockGuard(self)
+    }
+
+    /// Waits for the lock without blocking the thread, which may run the async test holding it
+    pub(crate) fn lock_async<'a, 'n>(
+        &'a self,
+        name: &'n str,
+        timeout: Duration,
+    ) -> LockAsync<'a, 'n> {
+        LockAsync {
+            lock: self,
+            name,
+            timeout,
+            waiting: None,
+        }
+    }
+
+    pub(crate) fn is_held_by_current_context(&self) -> bool {
+        self.holder
+            .lock()
+            .as_ref()
+            .is_some_and(|holder| holder.context == context::current_id())
+    }
+}
+
+/// Releases the lock on drop, even if the test panics
+#[doc(hidden)]
+pub struct StaticLockGuard<'a>(&'a StaticLock);
+
+impl Drop for StaticLockGuard<'_> {
+    fn drop(&mut self) {
+        *self.0.holder.lock() = None;
+        self.0.released.notify_one();
+        for (_, waker) in self.0.wakers.lock().drain() {
+            waker.wake();
+        }
+    }
+}
+
+/// Future of [`StaticLock::lock_async`]
+pub(crate) struct LockAsync<'a, 'n> {
+    lock: &'a StaticLock,
+    name: &'n str,
+    timeout: Duration,
+    /// The id and the deadline of the waiter, set on the first poll waiting for the lock
+    waiting: Option<(u64, Instant)>,
+}
+
+impl LockAsync<'_, '_> {
+    /// Removes the waker from the lock and the timer
+    fn stop_waiting(&mut self) {
+        if let Some((id, deadline)) = self.waiting.take() {
+            self.lock.wakers.lock().remove(&id);
+            timer().cancel(deadline, id);
+        }
+    }
+}
+
+impl<'a> Future for LockAsync<'a, '_> {
+    type Output = StaticLockGuard<'a>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+        let this = self.get_mut();
+        let (name, timeout) = (this.name, this.timeout);
+        let mut holder = this.lock.holder.lock();
+        let Some(Holder { context, test }) = &*holder else {
+            *holder = Some(Holder::current());
+            drop(holder);
+            this.stop_waiting();
+            return Poll::Ready(StaticLockGuard(this.lock));
+        };
+        if *context == context::current_id() {
+            panic!("the lock of `{name}` is already acquired by `{test}`");
+        }
+        let now = Instant::now();
+        let (id, deadline) = *this
+            .waiting
+            .get_or_insert_with(|| (NEXT_ID.fetch_add(1, Ordering::Relaxed), now + timeout));
+        if now >= deadline {
+            panic!(
+                "timed out after {timeout:?} waiting for the lock of `{name}` held by `{test}`. Set {LOCK_TIMEOUT_ENV} to wait longer"
+            );
+        }
+        // Registered while the holder is locked, so that the release cannot be missed. A waker
+        // of a previous poll is replaced.
+        this.lock.wakers.lock().insert(id, cx.waker().clone());
+        timer().wake_at(deadline, id, cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl Drop for LockAsync<'_, '_> {
+    fn drop(&mut self) {
+        self.stop_waiting();
+    }
+}
+
+/// Wakes the async waiters at their deadlines without depending on the timer of a runtime.
+/// A single thread serves all the locks.
+#[derive(Default)]
+struct Timer {
+    deadlines: Mutex<BTreeMap<(Instant, u64), Waker>>,
+    changed: Condvar,
+}
+
+fn timer() -> &'static Timer {
+    static TIMER: OnceLock<Timer> = OnceLock::new();
+    TIMER.get_or_init(|| {
+        thread::Builder::new()
+            .name("mry-lock-timer".into())
+            .spawn(|| timer().run())
+            .expect("failed to spawn the timer of mry locks");
+        Timer::default()
+    })
+}
+
+impl Timer {
+    /// Wakes the waiter at the deadline, replacing its previous waker
+    fn wake_at(&self, deadline: Instant, id: u64, waker: Waker) {
+        self.deadlines.lock().insert((deadline, id), waker);
+        self.changed.notify_one();
+    }
+
+    fn cancel(&self, deadline: Instant, id: u64) {
+        self.deadlines.lock().remove(&(deadline, id));
+    }
+
+    fn run(&self) {
+        let mut deadlines = self.deadlines.lock();
+        loop {
+            let now = Instant::now();
+            while let Some(entry) = deadlines.first_entry() {
+                if entry.key().0 > now {
+                    break;
+                }
+                entry.remove().wake();
+            }
+            match deadlines.keys().next() {
+                Some(&(deadline, _)) => {
+                    self.changed.wait_until(&mut deadlines, deadline);
+                }
+                None => self.changed.wait(&mut deadlines),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{atomic::AtomicBool, mpsc, Arc},
+        task::Wake,
+    };
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Woken(AtomicBool);
+
+    impl Wake for Woken {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    fn poll<F: Future + Unpin>(future: &mut F, woken: &Arc<Woken>) -> Poll<F::Output> {
+        let waker = Waker::from(woken.clone());
+        Pin::new(future).poll(&mut std::task::Context::from_waker(&waker))
+    }
+
+    #[test]
+    fn held_by_current_context() {
+        let lock = StaticLock::default();
+        assert!(!lock.is_held_by_current_context());
+
+        let guard = lock.lock("meow", DEFAULT_LOCK_TIMEOUT);
+        assert!(lock.is_held_by_current_context());
+
+        drop(guard);
+        assert!(!lock.is_held_by_current_context());
+    }
+
+    #[test]
+    fn not_held_by_other_thread() {
+        let lock = Arc::new(StaticLock::default());
+        let _guard = lock.lock("meow", DEFAULT_LOCK_TIMEOUT);
+
+        let cloned = lock.clone();
+        assert!(!thread::spawn(move || cloned.is_held_by_current_context())
+            .join()
+            .unwrap());
+    }
+
+    #[test]
+    fn waits_for_other_thread() {
+        let lock = Arc::new(StaticLock::default());
+        let (locked_tx, locked_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+
+        let cloned = lock.clone();
+        let holder = thread::spawn(move || {
+            let _guard = cloned.lock("meow", DEFAULT_LOCK_TIMEOUT);
+            locked_tx.send(()).unwrap();
+            release_rx.recv().unwrap();
+        });
+        locked_rx.recv().unwrap();
+        release_tx.send(()).unwrap();
+
+        let _guard = lock.lock("meow", DEFAULT_LOCK_TIMEOUT);
+        assert!(lock.is_held_by_current_context());
+        holder.join().unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "waiting for the lock of `meow` held by `holder_test`")]
+    fn timeout_names_holder() {
+        let lock = Arc::new(StaticLock::default());
+        let (locked_tx, locked_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+
+        let cloned = lock.clone();
+        thread::Builder::new()
+            .name("holder_test".into())
+            .spawn(move || {
+                let _guard = cloned.lock("meow", DEFAULT_LOCK_TIMEOUT);
+                locked_tx.send(()).unwrap();
+                let _ = release_rx.recv();
+            })
+            .unwrap();
+        locked_rx.recv().unwrap();
+
+        let _release = release_tx;
+        lock.lock("meow", Duration::from_millis(10));
+    }
+
+    #[test]
+    #[should_panic(expected = "the lock of `meow` is already acquired by")]
+    fn panics_on_relock_in_same_context() {
+        let lock = StaticLock::default();
+        let _guard = lock.lock("meow", DEFAULT_LOCK_TIMEOUT);
+        lock.lock("meow", DEFAULT_LOCK_TIMEOUT);
+    }
+
+    #[test]
+    fn async_lock_waits_without_blocking() {
+        let lock = Arc::new(StaticLock::default());
+        let (locked_tx, locked_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+
+        let cloned = lock.clone();
+        let holder = thread::spawn(move || {
+            let _guard = cloned.lock("meow", DEFAULT_LOCK_TIMEOUT);
+            locked_tx.send(()).unwrap();
+            release_rx.recv().unwrap();
+        });
+        locked_rx.recv().unwrap();
+
+        let woken = Arc::new(Woken::default());
+        let mut future = lock.lock_async("meow", DEFAULT_LOCK_TIMEOUT);
+        assert!(poll(&mut future, &woken).is_pending());
+
+        release_tx.send(()).unwrap();
+        holder.join().unwrap();
+        assert!(woken.0.load(Ordering::SeqCst));
+        let guard = poll(&mut future, &woken);
+        assert!(guard.is_ready());
+        assert!(lock.is_held_by_current_context());
+    }
+
+    #[test]
+    #[should_panic(expected = "waiting for the lock of `meow` held by `holder_test`")]
+    fn async_timeout_names_holder() {
+        let lock = Arc::new(StaticLock::default());
+        let (locked_tx, locked_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+
+        let cloned = lock.clone();
+        thread::Builder::new()
+            .name("holder_test".into())
+            .spawn(move || {
+                let _guard = cloned.lock("meow", DEFAULT_LOCK_TIMEOUT);
+                locked_tx.send(()).unwrap();
+                let _ = release_rx.recv();
+            })
+            .unwrap();
+        locked_rx.recv().unwrap();
+
+        let _release = release_tx;
+        let woken = Arc::new(Woken::default());
+        let mut future = lock.lock_async("meow", Duration::from_millis(10));
+        assert!(poll(&mut future, &woken).is_pending());
+        // Woken at the deadline
+        while !woken.0.load(Ordering::SeqCst) {
+            thread::yield_now();
+        }
+        let _ = poll(&mut future, &woken);
+    }
+
+    #[test]
+    fn async_waiter_keeps_one_waker() {
+        let lock = Arc::new(StaticLock::default());
+        let (locked_tx, locked_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+
+        let cloned = lock.clone();
+        let holder = thread::spawn(move || {
+            let _guard = cloned.lock("meow", DEFAULT_LOCK_TIMEOUT);
+            locked_tx.send(()).unwrap();
+            release_rx.recv().unwrap();
+        });
+        locked_rx.recv().unwrap();
+
+        let woken = Arc::new(Woken::default());
+        let mut future = lock.lock_async("meow", DEFAULT_LOCK_TIMEOUT);
+        assert!(poll(&mut future, &woken).is_pending());
+        assert!(poll(&mut future, &woken).is_pending());
+        let (id, deadline) = future.waiting.unwrap();
+        assert_eq!(lock.wakers.lock().len(), 1);
+        assert!(timer().deadlines.lock().contains_key(&(deadline, id)));
+
+        drop(future);
+        assert!(lock.wakers.lock().is_empty());
+        assert!(!timer().deadlines.lock().contains_key(&(deadline, id)));
+
+        release_tx.send(()).unwrap();
+        holder.join().unwrap();
+    }
+
+    #[test]
+    fn async_lock_cancels_the_timer() {
+        let lock = Arc::new(StaticLock::default());
+        let (locked_tx, locked_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+
+        let cloned = lock.clone();
+        let holder = thread::spawn(move || {
+            let _guard = cloned.lock("meow", DEFAULT_LOCK_TIMEOUT);
+            locked_tx.send(()).unwrap();
+            release_rx.recv().unwrap();
+        });
+        locked_rx.recv().unwrap();
+
+        let woken = Arc::new(Woken::default());
+        let mut future = lock.lock_async("meow", DEFAULT_LOCK_TIMEOUT);
+        assert!(poll(&mut future, &woken).is_pending());
+        let (id, deadline) = future.waiting.unwrap();
+
+        release_tx.send(()).unwrap();
+        holder.join().unwrap();
+        assert!(poll(&mut future, &woken).is_ready());
+        assert!(lock.wakers.lock().is_empty());
+        assert!(!timer().deadlines.lock().contains_key(&(deadline, id)));
+    }
+}
@@ -0,0 +1,148 @@
+use std::vec;
+
+/// Items returned by `returns_stream` as an iterator or a stream, with controls of how the
+/// stream polls them
+#[derive(Debug, Clone)]
+pub struct Items<T> {
+    items: Vec<T>,
+    pending_between: bool,
+    never_ending: bool,
+}
+
+impl<T> Items<T> {
+    pub fn new(items: impl IntoIterator<Item = T>) -> Self {
+        Self {
+            items: items.into_iter().collect(),
+            pending_between: false,
+            never_ending: false,
+        }
+    }
+
+    /// The stream returns `Poll::Pending` once before each item but the first, waking the task
+    /// immediately. An iterator ignores this.
+    pub fn pending_between(mut self) -> Self {
+        self.pending_between = true;
+        self
+    }
+
+    /// The stream stays pending after the items instead of ending. An iterator panics there,
+    /// since it cannot wait.
+    pub fn never_ending(mut self) -> Self {
+        self.never_ending = true;
+        self
+    }
+}
+
+impl<T, E> Items<Result<T, E>> {
+    /// Ends the items with `Err(error)`
+    pub fn ending_with_error(mut self, error: E) -> Self {
+        self.items.push(Err(error));
+        self
+    }
+}
+
+impl<T, I: IntoIterator<Item = T>> From<I> for Items<T> {
+    fn from(items: I) -> Self {
+        Self::new(items)
+    }
+}
+
+/// Iterator or stream over [`Items`]
+#[doc(hidden)]
+#[cfg_attr(not(feature = "futures"), allow(dead_code))]
+pub struct ItemsIter<T> {
+    items: vec::IntoIter<T>,
+    pending_between: bool,
+    never_ending: bool,
+    started: bool,
+    pended: bool,
+}
+
+// Items are moved out, never pinned.
+impl<T> Unpin for ItemsIter<T> {}
+
+impl<T> From<Items<T>> for ItemsIter<T> {
+    fn from(items: Items<T>) -> Self {
+        Self {
+            items: items.items.into_iter(),
+            pending_between: items.pending_between,
+            never_ending: items.never_ending,
+            started: false,
+            pended: false,
+        }
+    }
+}
+
+impl<T> Iterator for ItemsIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let item = self.items.next();
+        if item.is_none() && self.never_ending {
+            panic!("`never_ending` items are returned as an iterator, which cannot wait for more items");
+        }
+        item
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T> futures_core::Stream for ItemsIter<T> {
+    type Item = T;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<T>> {
+        use std::task::Poll;
+
+        let this = self.get_mut();
+        if this.pending_between && this.started && !this.pended && this.items.len() > 0 {
+            this.pended = true;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        this.pended = false;
+        match this.items.next() {
+            Some(item) => {
+                this.started = true;
+                Poll::Ready(Some(item))
+            }
+            None if this.never_ending => Poll::Pending,
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+/// Outputs made from [`Items`] by `returns_stream`, like `Box<dyn Iterator<Item = T> + Send>`
+#[doc(hidden)]
+pub trait FromItems<T> {
+    fn from_items(items: Items<T>) -> Self;
+}
+
+impl<T: Send + 'static> FromItems<T> for Box<dyn Iterator<Item = T> + Send> {
+    fn from_items(items: Items<T>) -> Self {
+        Box::new(ItemsIter::from(items))
+    }
+}
+
+impl<T: Send + Sync + 'static> FromItems<T> for Box<dyn Iterator<Item = T> + Send + Sync> {
+    fn from_items(items: Items<T>) -> Self {
+        Box::new(ItemsIter::from(items))
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T: Send + 'static> FromItems<T>
+    for std::pin::Pin<Box<dyn futures_core::Stream<Item = T> + Send>>
+{
+    fn from_items(items: Items<T>) -> Self {
+        Box::pin(ItemsIter::from(items))
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T: Send + 'static> FromItems<T> for Box<dyn futures_core::Stream<Item = T> + Send + Unpin> {
+    fn from_items(items: Items<T>) -> Self {
+        Box::new(ItemsIter::from(items))
+    }
+}
@@ -0,0 +1,82 @@
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+/// The mutex of mock containers. With the `shuttle` feature, a mutex created in a Shuttle
+/// execution also locks a mutex of Shuttle, so that its scheduler sees mocks shared by threads.
+#[doc(hidden)]
+pub struct Mutex<T: ?Sized> {
+    #[cfg(feature = "shuttle")]
+    model: Option<shuttle::sync::Mutex<()>>,
+    data: parking_lot::Mutex<T>,
+}
+
+impl<T> Mutex<T> {
+    pub(crate) fn new(data: T) -> Self {
+        Self {
+            #[cfg(feature = "shuttle")]
+            model: shuttle::current::try_get_current_task().map(|_| shuttle::sync::Mutex::new(())),
+            data: parking_lot::Mutex::new(data),
+        }
+    }
+
+    /// Creates a mutex never seen by Shuttle, for mocks that outlive an execution
+    pub(crate) fn unmodeled(data: T) -> Self {
+        Self {
+            #[cfg(feature = "shuttle")]
+            model: None,
+            data: parking_lot::Mutex::new(data),
+        }
+    }
+}
+
+impl<T: ?Sized> Mutex<T> {
+    pub(crate) fn lock(&self) -> MutexGuard<'_, T> {
+        #[cfg(feature = "shuttle")]
+        let model = self.model.as_ref().map(|model| {
+            model
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+        });
+        MutexGuard {
+            data: self.data.lock(),
+            #[cfg(feature = "shuttle")]
+            _model: model,
+        }
+    }
+}
+
+impl<T: Default> Default for Mutex<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Mutex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.data.fmt(f)
+    }
+}
+
+/// Unlocks the data before the mutex of Shuttle, which is locked first
+#[doc(hidden)]
+pub struct MutexGuard<'a, T: ?Sized> {
+    data: parking_lot::MutexGuard<'a, T>,
+    #[cfg(feature = "shuttle")]
+    _model: Option<shuttle::sync::MutexGuard<'a, ()>>,
+}
+
+impl<T: ?Sized> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.data
+    }
+}
+
+impl<T: ?Sized> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+}
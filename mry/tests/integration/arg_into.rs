@@ -0,0 +1,72 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+#[mry::mry]
+struct Client {}
+
+#[mry::mry(arg_into(body = Vec<u8>, path = PathBuf, label = String))]
+impl Client {
+    fn send(&self, body: impl AsRef<[u8]>) -> usize {
+        body.as_ref().len()
+    }
+
+    fn load<P: AsRef<Path>>(&self, path: &P) -> String {
+        path.as_ref().display().to_string()
+    }
+
+    fn tag<L>(&self, label: L, count: usize) -> String
+    where
+        L: Display,
+    {
+        label.to_string().repeat(count)
+    }
+}
+
+#[mry::mry(arg_into(name = String))]
+fn greet<N: Into<String> + Clone + 'static>(name: N) -> String {
+    format!("Hello, {}", name.into())
+}
+
+#[test]
+fn impl_trait_argument() {
+    let mut client = mry::new!(Client {});
+    client.mock_send(b"ping".to_vec()).returns(1);
+    client.mock_send(mry::Any).returns(2);
+
+    assert_eq!(client.send("ping"), 1);
+    assert_eq!(client.send(b"ping"), 1);
+    assert_eq!(client.send(vec![0u8]), 2);
+    client.mock_send(vec![0u8]).assert_called(1);
+}
+
+#[test]
+fn generic_argument() {
+    let mut client = mry::new!(Client {});
+    client.mock_load(PathBuf::from("a.txt")).returns("a".into());
+    client.mock_tag("cat".to_string(), 2).returns("meow".into());
+
+    assert_eq!(client.load(&"a.txt"), "a");
+    assert_eq!(client.load(&PathBuf::from("a.txt")), "a");
+    assert_eq!(client.tag("cat", 2), "meow");
+    assert_eq!(client.tag(String::from("cat"), 2), "meow");
+    client.mock_load(PathBuf::from("a.txt")).assert_called(2);
+}
+
+#[test]
+fn calls_real_implementation() {
+    let mut client = mry::new!(Client {});
+    client.mock_send(mry::Any).calls_real_impl();
+
+    assert_eq!(client.send("ping"), 4);
+}
+
+#[test]
+#[mry::lock(greet::<&str>)]
+fn function() {
+    mock_greet::<&str>("Cat".to_string()).returns("meow".into());
+
+    assert_eq!(greet("Cat"), "meow");
+    mock_greet::<&str>("Cat".to_string()).assert_called(1);
+}
@@ -24,3 +24,14 @@ async fn meow_called2() {
 
     let _ = Cat::meow2(&cat, 2).await;
 }
+
+#[async_std::test]
+async fn local_variant_is_implemented() {
+    let mut cat = MockCat::default();
+
+    cat.mock_meow(2).returns("Called");
+    cat.mock_meow2(2).returns_once(NonClone);
+
+    assert_eq!(LocalCat::meow(&cat, 2).await, "Called");
+    let _ = LocalCat::meow2(&cat, 2).await;
+}
@@ -0,0 +1,107 @@
+use std::{path::PathBuf, sync::Mutex};
+
+use mry::ByRef;
+
+#[mry::mry]
+struct Storage {}
+
+struct File {
+    path: PathBuf,
+    // Not `Clone`
+    _handle: Mutex<()>,
+}
+
+impl File {
+    fn open(path: &str) -> Self {
+        Self {
+            path: path.into(),
+            _handle: Mutex::new(()),
+        }
+    }
+}
+
+/// An owned guard, which is not `Clone`
+struct Lease(u8);
+
+#[mry::mry(by_ref(File = |file: &File| -> PathBuf { file.path.clone() }, Lease))]
+impl Storage {
+    fn size(&self, file: &File) -> usize {
+        file.path.as_os_str().len()
+    }
+
+    fn read(&self, lease: Lease, offset: u8) -> u8 {
+        lease.0 + offset
+    }
+}
+
+#[mry::mry(by_ref_fns(checksum))]
+fn checksum(file: File, seed: u8) -> u8 {
+    file.path.as_os_str().len() as u8 + seed
+}
+
+#[test]
+fn matches_by_reference() {
+    let mut storage = mry::new!(Storage {});
+    storage
+        .mock_size(|file: &File| file.path.ends_with("a.txt"))
+        .returns(1);
+    storage.mock_size(mry::Any).returns(2);
+
+    assert_eq!(storage.size(&File::open("dir/a.txt")), 1);
+    assert_eq!(storage.size(&File::open("dir/b.txt")), 2);
+}
+
+#[test]
+fn records_projection() {
+    let mut storage = mry::new!(Storage {});
+    storage
+        .mock_size(mry::Any)
+        .returns_with(|file: ByRef<File, PathBuf>| file.projection().as_os_str().len() * 10);
+
+    assert_eq!(storage.size(&File::open("a.txt")), 50);
+
+    storage
+        .mock_size(ByRef::projected(PathBuf::from("a.txt")))
+        .assert_called(1);
+    storage
+        .mock_size(ByRef::projected(PathBuf::from("b.txt")))
+        .assert_called(0);
+}
+
+#[test]
+#[should_panic(expected = "is matched by reference")]
+fn cannot_match_recorded_calls_by_reference() {
+    let mut storage = mry::new!(Storage {});
+    storage.mock_size(mry::Any).returns(1);
+    storage.size(&File::open("a.txt"));
+
+    storage
+        .mock_size(|file: &File| file.path.ends_with("a.txt"))
+        .assert_called(1);
+}
+
+#[test]
+fn matches_guard() {
+    let mut storage = mry::new!(Storage {});
+    storage
+        .mock_read(|lease: &Lease| lease.0 == 1, 2)
+        .returns(10);
+    storage.mock_read(mry::Any, 2).calls_real_impl();
+
+    assert_eq!(storage.read(Lease(1), 2), 10);
+    assert_eq!(storage.read(Lease(2), 2), 4);
+}
+
+#[test]
+#[mry::lock(checksum)]
+fn by_ref_fns() {
+    mock_checksum(
+        |file: &File| file.path.ends_with("a.txt"),
+        |seed: &u8| *seed == 1,
+    )
+    .returns(0);
+    mock_checksum(mry::Any, |seed: &u8| *seed > 1).calls_real_impl();
+
+    assert_eq!(checksum(File::open("a.txt"), 1), 0);
+    assert_eq!(checksum(File::open("b.txt"), 2), 7);
+}
@@ -0,0 +1,218 @@
+#[mry::mry]
+#[derive(Default, Clone)]
+struct Cat {
+    name: String,
+}
+
+#[mry::mry]
+impl Cat {
+    fn meow(&self, count: usize) -> String {
+        format!("{}: {}", self.name, "meow".repeat(count))
+    }
+}
+
+#[mry::mry(clone = "share")]
+#[derive(Default, Clone)]
+struct SharedCat {
+    name: String,
+}
+
+#[mry::mry]
+impl SharedCat {
+    fn meow(&self, count: usize) -> String {
+        format!("{}: {}", self.name, "meow".repeat(count))
+    }
+}
+
+#[mry::mry(clone = "fresh")]
+#[derive(Default, Clone)]
+struct FreshCat {
+    name: String,
+}
+
+#[mry::mry]
+impl FreshCat {
+    fn meow(&self, count: usize) -> String {
+        format!("{}: {}", self.name, "meow".repeat(count))
+    }
+}
+
+#[mry::mry(clone = "deep")]
+#[derive(Default, Clone)]
+struct DeepCat {
+    name: String,
+}
+
+#[mry::mry]
+impl DeepCat {
+    fn meow(&self, count: usize) -> String {
+        format!("{}: {}", self.name, "meow".repeat(count))
+    }
+}
+
+#[mry::mry(clone = "deep")]
+trait Store {
+    fn get(&self, key: String) -> Option<String>;
+}
+
+#[mry::mry(clone = "fresh", fallback = "default")]
+trait Counter {
+    fn count(&self) -> usize;
+}
+
+#[mry::mry(fallback = "default")]
+trait Feeder {
+    fn portions(&self) -> usize;
+}
+
+#[mry::mry]
+trait Bowl {
+    fn size(&self) -> usize;
+}
+
+#[test]
+fn clone_before_mock_is_independent() {
+    let mut cat = mry::new!(Cat {
+        name: "Tama".into()
+    });
+    let clone = cat.clone();
+
+    cat.mock_meow(2).returns("purr".into());
+
+    assert_eq!(clone.meow(2), "Tama: meowmeow");
+    cat.mock_meow(2).assert_called(0);
+}
+
+#[test]
+fn clone_after_mock_shares_rules() {
+    let mut cat = mry::new!(Cat {
+        name: "Tama".into()
+    });
+    cat.mock_meow(2).returns("purr".into());
+    let clone = cat.clone();
+
+    assert_eq!(clone.meow(2), "purr");
+    cat.mock_meow(2).assert_called(1);
+}
+
+#[test]
+fn shared_clone_before_mock_shares_rules() {
+    let mut cat = mry::new!(SharedCat {
+        name: "Tama".into()
+    });
+    let clone = cat.clone();
+
+    cat.mock_meow(2).returns("purr".into());
+
+    assert_eq!(clone.meow(2), "purr");
+    cat.mock_meow(2).assert_called(1);
+}
+
+#[test]
+fn fork_copies_rules() {
+    let mut cat = mry::new!(Cat {
+        name: "Tama".into()
+    });
+    cat.mock_meow(2).returns("purr".into());
+    let mut fork = Cat {
+        mry: cat.mry.fork(),
+        ..cat.clone()
+    };
+
+    fork.mock_meow(3).returns("hiss".into());
+
+    assert_eq!(fork.meow(2), "purr");
+    assert_eq!(fork.meow(3), "hiss");
+    cat.mock_meow(mry::Any).assert_called(0);
+    fork.mock_meow(mry::Any).assert_called(2);
+}
+
+#[test]
+fn share_shares_rules() {
+    let mut cat = mry::new!(DeepCat {
+        name: "Tama".into()
+    });
+    let shared = DeepCat {
+        mry: cat.mry.share(),
+        ..cat.clone()
+    };
+
+    cat.mock_meow(2).returns("purr".into());
+
+    assert_eq!(shared.meow(2), "purr");
+}
+
+#[test]
+fn fresh_clone_has_no_rules() {
+    let mut cat = mry::new!(FreshCat {
+        name: "Tama".into()
+    });
+    cat.mock_meow(2).returns("purr".into());
+    let mut clone = cat.clone();
+
+    assert_eq!(clone.meow(2), "Tama: meowmeow");
+    clone.mock_meow(2).returns("hiss".into());
+    assert_eq!(cat.meow(2), "purr");
+}
+
+#[test]
+fn deep_clone_copies_rules() {
+    let mut cat = mry::new!(DeepCat {
+        name: "Tama".into()
+    });
+    cat.mock_meow(2).returns("purr".into());
+    let mut clone = cat.clone();
+
+    clone.mock_meow(2).returns("hiss".into());
+
+    assert_eq!(cat.meow(2), "purr");
+    assert_eq!(clone.meow(2), "purr");
+    cat.mock_meow(2).assert_called(1);
+    clone.mock_meow(2).assert_called(1);
+}
+
+#[test]
+fn deep_clone_of_trait_mock() {
+    let mut store = MockStore::named("primary");
+    store.mock_get("a").returns(Some("apple".into()));
+    let mut clone = store.clone();
+    clone.mry.set_name("replica");
+
+    clone.mock_get("b").returns(None);
+
+    assert_eq!(clone.get("a".into()), Some("apple".into()));
+    assert_eq!(store.mry.name(), Some("primary".into()));
+    store.mock_get("a").assert_called(0);
+}
+
+#[test]
+fn fresh_clone_of_trait_mock_keeps_fallback() {
+    let mut counter = MockCounter::default();
+    counter.mock_count().returns(3);
+
+    assert_eq!(counter.count(), 3);
+    assert_eq!(counter.clone().count(), 0);
+}
+
+#[test]
+fn clone_of_fallback_trait_mock_before_mock_is_independent() {
+    let mut feeder = MockFeeder::default();
+    let clone = feeder.clone();
+
+    feeder.mock_portions().returns(3);
+
+    assert_eq!(clone.portions(), 0);
+    feeder.mock_portions().assert_called(0);
+}
+
+#[test]
+fn clone_of_nice_trait_mock_before_mock_is_independent() {
+    let mut bowl = MockBowl::nice();
+    let clone = bowl.clone();
+
+    bowl.mock_size().returns(3);
+
+    assert_eq!(clone.size(), 0);
+    assert_eq!(bowl.size(), 3);
+    bowl.mock_size().assert_called(1);
+}
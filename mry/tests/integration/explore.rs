@@ -0,0 +1,69 @@
+#[derive(Clone, Debug, PartialEq)]
+enum FetchError {
+    Timeout,
+    NotFound,
+}
+
+#[mry::mry]
+struct Client {}
+
+#[mry::mry]
+impl Client {
+    fn fetch(&self, key: &str) -> Result<String, FetchError> {
+        Ok(key.to_string())
+    }
+}
+
+fn fetch_with_retry(client: &Client, key: &str) -> Option<String> {
+    match client.fetch(key) {
+        Ok(value) => Some(value),
+        Err(FetchError::Timeout) => client.fetch(key).ok(),
+        Err(FetchError::NotFound) => None,
+    }
+}
+
+#[test]
+fn explore_outcomes() {
+    let mut outcomes = Vec::new();
+    let runs = mry::explore(|| {
+        let mut client = mry::new!(Client {});
+        client.mock_fetch(mry::Any).returns_one_of([
+            Ok("value".to_string()),
+            Err(FetchError::Timeout),
+            Err(FetchError::NotFound),
+        ]);
+
+        outcomes.push(fetch_with_retry(&client, "key"));
+    });
+
+    assert_eq!(runs, 5);
+    assert_eq!(
+        outcomes,
+        [Some("value".into()), Some("value".into()), None, None, None]
+    );
+}
+
+#[test]
+#[should_panic(
+    expected = "gave up\n`mry::explore` failed in run 3 with the choices:\n  Client::fetch returned #1 of 2: Err(Timeout)\n  Client::fetch returned #1 of 2: Err(Timeout)"
+)]
+fn explore_reports_failure() {
+    mry::explore(|| {
+        let mut client = mry::new!(Client {});
+        client
+            .mock_fetch(mry::Any)
+            .returns_one_of([Ok("value".to_string()), Err(FetchError::Timeout)]);
+
+        fetch_with_retry(&client, "key").expect("gave up");
+    });
+}
+
+#[test]
+fn returns_first_outside_explore() {
+    let mut client = mry::new!(Client {});
+    client
+        .mock_fetch(mry::Any)
+        .returns_one_of([Err(FetchError::NotFound), Err(FetchError::Timeout)]);
+
+    assert_eq!(client.fetch("key"), Err(FetchError::NotFound));
+}
@@ -0,0 +1,185 @@
+use mry::Fallback;
+
+#[mry::mry]
+#[derive(Default, Clone, PartialEq, Debug)]
+struct Cat {
+    name: String,
+}
+
+#[mry::mry]
+impl Cat {
+    fn meow(&self, count: usize) -> String {
+        "meow".repeat(count)
+    }
+
+    fn lives(&self) -> u8 {
+        9
+    }
+
+    fn feed(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+#[mry::mry]
+trait Repo {
+    fn count(&self) -> usize;
+    fn find(&self, id: usize) -> Option<String>;
+}
+
+#[mry::mry(fallback = "default")]
+trait Quiet {
+    fn volume(&self) -> u8;
+}
+
+#[mry::mry]
+trait Store {
+    fn open(&self) -> Cat;
+}
+
+#[mry::mry]
+#[derive(Default)]
+struct Counter {}
+
+#[mry::mry(fallback = "default")]
+impl Counter {
+    fn count(&self) -> usize {
+        42
+    }
+
+    fn name(&self) -> String {
+        "counter".into()
+    }
+}
+
+#[mry::mry]
+#[derive(Default)]
+struct Decoder<T> {
+    value: T,
+}
+
+#[mry::mry(fallback = "default")]
+impl<T: Default + Clone + Send + 'static> Decoder<T> {
+    fn value(&self) -> T {
+        self.value.clone()
+    }
+
+    fn decode<U: Default + Send + 'static>(&self, _input: String) -> U {
+        unimplemented!()
+    }
+}
+
+#[test]
+fn nice_mock_returns_default() {
+    let mut repo = MockRepo::nice();
+    repo.mock_find(1).returns(Some("Tama".into()));
+
+    assert_eq!(repo.count(), 0);
+    assert_eq!(repo.find(1), Some("Tama".into()));
+    assert_eq!(repo.find(2), None);
+
+    repo.mock_count().assert_called(1);
+}
+
+#[test]
+#[should_panic(expected = "mock not found for Repo::count")]
+fn strict_by_default() {
+    let mut repo = MockRepo::default();
+    repo.mock_find(1).returns(None);
+
+    repo.count();
+}
+
+#[test]
+fn fallback_real_on_object() {
+    let mut cat = Cat::default();
+    cat.mry.fallback(Fallback::CallsRealImpl);
+    cat.mock_lives().returns(3);
+
+    assert_eq!(cat.meow(2), "meowmeow");
+    assert_eq!(cat.lives(), 3);
+}
+
+#[test]
+fn fallback_default_on_object() {
+    let mut cat = Cat::default();
+    cat.mry.fallback(Fallback::Default);
+    cat.mock_lives().returns(3);
+
+    assert_eq!(cat.meow(2), "");
+    assert_eq!(cat.lives(), 3);
+}
+
+#[test]
+fn fallback_with_error() {
+    let mut cat = Cat::default();
+    cat.mry
+        .fallback_with(|| Err::<(), String>("not mocked".into()));
+    cat.mock_lives().returns(3);
+
+    assert_eq!(cat.feed(), Err("not mocked".into()));
+    cat.mock_feed().assert_called(1);
+}
+
+#[test]
+fn fallback_attribute_on_trait() {
+    let quiet = MockQuiet::default();
+
+    assert_eq!(quiet.volume(), 0);
+}
+
+#[test]
+#[should_panic(expected = "mock not found for Quiet::volume")]
+fn object_fallback_overrides_attribute() {
+    let mut quiet = MockQuiet::default();
+    quiet.mry.fallback(Fallback::Panic);
+
+    quiet.volume();
+}
+
+#[test]
+fn fallback_attribute_on_impl() {
+    let mut counter = Counter::default();
+    // Without any mock, the real implementation is called.
+    assert_eq!(counter.count(), 42);
+
+    counter.mock_name().returns("mocked".into());
+    assert_eq!(counter.count(), 0);
+    assert_eq!(counter.name(), "mocked");
+}
+
+#[test]
+#[should_panic(expected = "does not implement `Default`")]
+fn nice_mock_panics_without_default() {
+    #[derive(Debug)]
+    struct NotDefault;
+
+    #[mry::mry]
+    trait Factory {
+        fn make(&self) -> NotDefault;
+    }
+
+    MockFactory::nice().make();
+}
+
+#[test]
+fn nice_mock_returns_default_struct() {
+    let store = MockStore::nice();
+
+    assert_eq!(store.open(), Cat::default());
+}
+
+#[test]
+fn fallback_attribute_on_generic_outputs() {
+    let mut decoder = Decoder::<u8> {
+        value: 3,
+        ..Default::default()
+    };
+    decoder
+        .mock_decode::<String>(mry::Any)
+        .returns("mocked".into());
+
+    assert_eq!(decoder.value(), 0);
+    assert_eq!(decoder.decode::<usize>("1".into()), 0);
+    assert_eq!(decoder.decode::<String>("1".into()), "mocked");
+}
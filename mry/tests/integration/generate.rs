@@ -0,0 +1,96 @@
+#[cfg(feature = "proptest")]
+use proptest::strategy::Strategy;
+
+#[mry::mry]
+struct Dice {}
+
+#[mry::mry]
+impl Dice {
+    fn roll(&self) -> u8 {
+        4
+    }
+
+    fn name(&self) -> String {
+        "d6".into()
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn returns_arbitrary() {
+    let mut dice = mry::new!(Dice {});
+    dice.mock_roll().returns_arbitrary();
+    dice.mock_name().returns_arbitrary();
+
+    let rolls: Vec<u8> = (0..32).map(|_| dice.roll()).collect();
+    dice.name();
+
+    assert!(rolls.iter().any(|roll| *roll != rolls[0]));
+    dice.mock_roll().assert_called(32);
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn returns_arbitrary_is_replayable() {
+    let rolls = || {
+        let mut dice = mry::new!(Dice {});
+        dice.mock_roll().returns_arbitrary();
+        (0..8).map(|_| dice.roll()).collect::<Vec<_>>()
+    };
+
+    assert_eq!(rolls(), rolls());
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn returns_arbitrary_from() {
+    let mut dice = mry::new!(Dice {});
+    dice.mock_roll().returns_arbitrary_from([1, 2, 3]);
+
+    assert_eq!(dice.roll(), 1);
+    assert_eq!(dice.roll(), 2);
+    assert_eq!(dice.roll(), 3);
+    assert_eq!(dice.roll(), 0);
+    assert_eq!(dice.mock_roll().generated(), ["1", "2", "3", "0"]);
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn records_generated_with_seed() {
+    let mut dice = mry::new!(Dice {});
+    dice.mock_roll().returns_arbitrary();
+
+    let roll = dice.roll();
+
+    let generated = dice.mock_roll().generated();
+    assert_eq!(generated.len(), 1);
+    assert!(generated[0].starts_with(&format!("{roll} (replay with MRY_SEED=")));
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+#[should_panic(expected = "Generated outputs: 1, 2")]
+fn assertion_shows_generated() {
+    let mut dice = mry::new!(Dice {});
+    dice.mock_roll().returns_arbitrary_from([1, 2]);
+    dice.roll();
+    dice.roll();
+
+    dice.mock_roll().assert_called(3);
+}
+
+#[cfg(feature = "proptest")]
+#[test]
+fn returns_from() {
+    let mut dice = mry::new!(Dice {});
+    dice.mock_roll().returns_from(1..=6u8);
+    dice.mock_name().returns_from(
+        proptest::collection::vec(b'a'..=b'z', 1..4)
+            .prop_map(|name| String::from_utf8(name).unwrap()),
+    );
+
+    for _ in 0..32 {
+        assert!((1..=6).contains(&dice.roll()));
+        assert!((1..4).contains(&dice.name().len()));
+    }
+}
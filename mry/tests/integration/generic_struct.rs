@@ -0,0 +1,149 @@
+use mry::Any;
+
+pub trait Decode {
+    fn decode(raw: &str) -> Self;
+}
+
+impl Decode for u8 {
+    fn decode(raw: &str) -> Self {
+        raw.parse().unwrap()
+    }
+}
+
+impl Decode for String {
+    fn decode(raw: &str) -> Self {
+        raw.to_string()
+    }
+}
+
+#[mry::mry]
+#[derive(Default)]
+struct Repo<T> {
+    items: Vec<T>,
+}
+
+#[mry::mry]
+impl<T: Clone + PartialEq + Send + 'static> Repo<T> {
+    fn first(&self) -> Option<T> {
+        self.items.first().cloned()
+    }
+
+    fn contains(&self, item: T) -> bool {
+        self.items.contains(&item)
+    }
+
+    fn get<U: Decode + Send + 'static>(&self, raw: &str) -> U {
+        U::decode(raw)
+    }
+
+    fn create(item: T) -> Self {
+        mry::new!(Self { items: vec![item] })
+    }
+}
+
+#[test]
+fn mock_each_instantiation() {
+    let mut numbers = Repo::<u8>::default();
+    let mut names = Repo::<String>::default();
+
+    numbers.mock_first().returns(Some(1));
+    numbers.mock_contains(3).returns(true);
+    names.mock_first().returns(Some("Tama".to_string()));
+    names.mock_contains("Tama").returns(false);
+
+    assert_eq!(numbers.first(), Some(1));
+    assert!(numbers.contains(3));
+    assert_eq!(names.first(), Some("Tama".to_string()));
+    assert!(!names.contains("Tama".to_string()));
+}
+
+#[test]
+fn generic_method_matches_only_requested_type() {
+    let mut repo = Repo::<u8>::default();
+
+    repo.mock_get::<u8>(Any).returns(3);
+    repo.mock_get::<String>(Any).calls_real_impl();
+
+    assert_eq!(repo.get::<u8>("1"), 3);
+    assert_eq!(repo.get::<String>("1"), "1");
+
+    repo.mock_get::<u8>(Any).assert_called(1);
+    repo.mock_get::<String>("1").assert_called(1);
+}
+
+#[test]
+#[should_panic(expected = "mock not found for Repo<T>::get")]
+fn generic_method_without_rule_for_type() {
+    let mut repo = Repo::<u8>::default();
+
+    repo.mock_get::<u8>(Any).returns(3);
+
+    repo.get::<String>("1");
+}
+
+#[test]
+#[mry::lock(Repo::<u8>::create)]
+fn static_method_of_generic_struct() {
+    Repo::<u8>::mock_create(Any).returns_with(|item| {
+        mry::new!(Repo {
+            items: vec![item, item]
+        })
+    });
+
+    assert_eq!(Repo::<u8>::create(2).items, vec![2, 2]);
+}
+
+#[test]
+#[mry::lock(Repo::<u8>::create, Repo::<String>::create)]
+fn static_method_keys_are_per_instantiation() {
+    Repo::<u8>::mock_create(Any).returns_with(|item| {
+        mry::new!(Repo {
+            items: vec![item, item]
+        })
+    });
+    Repo::<String>::mock_create(Any).calls_real_impl();
+
+    assert_eq!(Repo::<u8>::create(2).items, vec![2, 2]);
+    assert_eq!(Repo::<String>::create("a".into()).items, vec!["a"]);
+}
+
+#[mry::mry]
+struct View<'a, T> {
+    items: &'a [T],
+}
+
+#[mry::mry]
+impl<'a, T: Clone + Send + 'static> View<'a, T> {
+    fn first(&self) -> Option<T> {
+        self.items.first().cloned()
+    }
+
+    fn label() -> String {
+        std::any::type_name::<T>().to_string()
+    }
+}
+
+#[test]
+fn lifetime_generic_struct_per_instantiation() {
+    let numbers = [1u8, 2];
+    let names = ["Tama".to_string()];
+    let mut number_view = mry::new!(View { items: &numbers });
+    let mut name_view = mry::new!(View { items: &names });
+
+    number_view.mock_first().returns(Some(3));
+    name_view.mock_first().calls_real_impl();
+
+    assert_eq!(number_view.first(), Some(3));
+    assert_eq!(name_view.first(), Some("Tama".to_string()));
+}
+
+#[test]
+#[mry::lock(View::<u8>::label, View::<String>::label)]
+fn lifetime_generic_static_method_keys_are_per_instantiation() {
+    View::<u8>::mock_label().returns("numbers".to_string());
+    View::<String>::mock_label().calls_real_impl();
+
+    assert_eq!(View::<u8>::label(), "numbers");
+    assert_eq!(View::<String>::label(), "alloc::string::String");
+    View::<u8>::mock_label().assert_called(1);
+}
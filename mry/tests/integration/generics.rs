@@ -55,10 +55,11 @@ where
 #[test]
 fn test() {
     let value = 42;
-    let _test = Test {
+    let test = Test {
         value: &value,
         mry: Default::default(),
     };
+    assert_eq!(*test.value, 42);
 }
 
 // https://github.com/ryo33/mry/issues/22
@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+#[mry::mry(handle)]
+trait Store: Send + Sync {
+    fn get(&self, key: &str) -> Option<String>;
+}
+
+#[mry::mry(handle = "controller")]
+trait Connection: Send + Sync {
+    fn handle(&self) -> u32;
+}
+
+#[mry::mry(handle, supertraits(Named))]
+trait Pet: Named + Send + Sync {
+    fn age(&self) -> u8;
+}
+
+#[mry::mry]
+trait Named {
+    fn name(&self) -> String;
+}
+
+#[mry::mry]
+#[derive(Default)]
+struct Cat {
+    name: String,
+}
+
+#[mry::mry(handle)]
+impl Cat {
+    fn meow(&self, count: usize) -> String {
+        format!("{}: {}", self.name, "meow".repeat(count))
+    }
+}
+
+#[mry::mry(handle = "store_handle")]
+impl Store for Cat {
+    fn get(&self, key: &str) -> Option<String> {
+        Some(format!("{}: {key}", self.name))
+    }
+}
+
+#[test]
+fn handle_of_trait_mock() {
+    let mock = MockStore::default();
+    let handle = mock.handle();
+    let store: Arc<dyn Store> = Arc::new(mock);
+
+    handle
+        .clone()
+        .mock_get("a")
+        .returns(Some("apple".to_string()));
+
+    assert_eq!(store.get("a"), Some("apple".to_string()));
+    handle.clone().mock_get("a").assert_called(1);
+}
+
+#[test]
+fn handle_from_another_thread() {
+    let mock = MockStore::default();
+    let handle = mock.handle();
+    let store: Arc<dyn Store> = Arc::new(mock);
+
+    std::thread::spawn({
+        let mut handle = handle.clone();
+        move || {
+            handle.mock_get(mry::Any).returns(None);
+        }
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(store.get("b"), None);
+}
+
+#[test]
+fn named_handle_of_trait_mock() {
+    let mock = MockConnection::default();
+    let mut controller = mock.controller();
+    let connection: Arc<dyn Connection> = Arc::new(mock);
+
+    controller.mock_handle().returns(3);
+
+    assert_eq!(connection.handle(), 3);
+    controller.mock_handle().assert_called(1);
+}
+
+#[test]
+fn handle_of_supertrait() {
+    let mock = MockPet::default();
+    let mut handle = mock.handle();
+    let pet: Arc<dyn Pet> = Arc::new(mock);
+
+    handle.mock_name().returns("Tama".to_string());
+    handle.mock_age().returns(3);
+
+    assert_eq!(pet.name(), "Tama");
+    assert_eq!(pet.age(), 3);
+}
+
+#[test]
+fn handle_of_struct_mock() {
+    let cat = mry::new!(Cat {
+        name: "Tama".to_string()
+    });
+    let mut handle = cat.handle();
+    let cat = Arc::new(cat);
+
+    handle.mock_meow(2).returns("purr".to_string());
+
+    assert_eq!(cat.meow(2), "purr");
+    handle.mock_meow(2).assert_called(1);
+}
+
+#[test]
+fn handle_of_trait_impl() {
+    let cat = mry::new!(Cat {
+        name: "Tama".to_string()
+    });
+    let mut handle = cat.store_handle();
+    let store: Arc<dyn Store> = Arc::new(cat);
+
+    handle.mock_get("a").returns(None);
+
+    assert_eq!(store.get("a"), None);
+    handle.mock_get("a").assert_called(1);
+}
+
+#[test]
+fn handle_is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync + Clone>(_: &T) {}
+
+    assert_send_sync(&MockStore::default().handle());
+    assert_send_sync(&Cat::default().handle());
+}
@@ -1,28 +1,50 @@
+mod arg_into;
 mod async_fn_in_trait;
 mod async_fn_trait_variant;
 mod async_method;
 mod async_trait;
 mod bounds;
+mod by_ref;
+mod clone_policy;
 mod complex_clone;
+mod explore;
+mod fallback;
 mod function_style_macro;
+mod generate;
+mod generic_struct;
 mod generics;
+mod handle;
 mod impl_trait;
 mod iterator;
 mod many_arguments;
+mod matchers;
+mod mock_fn;
 mod mock_trait;
+mod mry_test;
+mod multi_thread_runtime;
 mod mut_param;
+mod named;
 mod nested_mock;
 mod not_clone;
+mod owned;
 mod partial_mock;
+mod reentrant;
 mod reference_and_pattern;
 mod returns_with_recursive_call;
+mod returns_with_self;
 mod simple_case;
 mod skip_arg;
 mod skip_fns;
+mod spy;
 mod static_function;
+mod stream;
+mod supertrait;
 mod track_caller;
+mod unsafe_fn;
 
 #[cfg(feature = "send_wrapper")]
 mod non_send;
 #[cfg(feature = "send_wrapper")]
 mod raw_pointer;
+#[cfg(feature = "shuttle")]
+mod shuttle;
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use mry::matchers::{contains_all, each, glob, has_entry, has_key, has_len, is_empty, unordered};
+
+#[mry::mry]
+struct Store {}
+
+#[mry::mry]
+impl Store {
+    fn get(&self, key: &str) -> Option<String> {
+        Some(key.to_string())
+    }
+
+    fn get_all(&self, keys: &[&str]) -> usize {
+        keys.len()
+    }
+
+    fn put(&self, values: &HashMap<String, u8>) -> bool {
+        !values.is_empty()
+    }
+}
+
+#[test]
+fn glob_matcher() {
+    let mut store = mry::new!(Store {});
+    store
+        .mock_get(glob("config/*.json"))
+        .returns(Some("{}".into()));
+    store.mock_get(mry::Any).returns(None);
+
+    assert_eq!(store.get("config/app.json"), Some("{}".into()));
+    assert_eq!(store.get("config/app.toml"), None);
+    store.mock_get(glob("config/*")).assert_called(2);
+}
+
+#[test]
+fn glob_matcher_for_elements() {
+    let mut store = mry::new!(Store {});
+    store
+        .mock_get_all([glob("*.rs"), glob("*.toml")])
+        .returns(0);
+
+    assert_eq!(store.get_all(&["lib.rs", "Cargo.toml"]), 0);
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn regex_matcher() {
+    let mut store = mry::new!(Store {});
+    store
+        .mock_get(mry::matchers::regex(r"^user-\d+$"))
+        .returns(Some("user".into()));
+    store.mock_get(mry::Any).returns(None);
+
+    assert_eq!(store.get("user-42"), Some("user".into()));
+    assert_eq!(store.get("user-x"), None);
+}
+
+#[test]
+fn collection_matchers() {
+    let mut store = mry::new!(Store {});
+    store.mock_get_all(unordered(["b", "a"])).returns(1);
+    store.mock_get_all(contains_all(["c"])).returns(2);
+    store.mock_get_all(each(glob("*.rs"))).returns(3);
+    store.mock_get_all(is_empty()).returns(4);
+    store.mock_get_all(has_len(5)).returns(5);
+
+    assert_eq!(store.get_all(&["a", "b"]), 1);
+    assert_eq!(store.get_all(&["a", "b", "c"]), 2);
+    assert_eq!(store.get_all(&["a.rs", "b.rs"]), 3);
+    assert_eq!(store.get_all(&[]), 3);
+    assert_eq!(store.get_all(&["1", "2", "3", "4", "5"]), 5);
+    store.mock_get_all(has_len(2)).assert_called(2);
+}
+
+#[test]
+fn map_matchers() {
+    let mut store = mry::new!(Store {});
+    store.mock_put(has_entry("id", 1)).returns(true);
+    store.mock_put(has_key(glob("tmp-*"))).returns(false);
+
+    assert!(store.put(&HashMap::from([("id".into(), 1), ("age".into(), 3)])));
+    assert!(!store.put(&HashMap::from([("tmp-1".into(), 1)])));
+}
+
+#[test]
+#[should_panic(
+    expected = "Expected Store::get to be called 1 times with (is empty), but it was called 0 times"
+)]
+fn described_matcher() {
+    let mut store = mry::new!(Store {});
+    store
+        .mock_get(
+            mry::ArgMatcher::Fn(Box::new(|key: &String| key.is_empty())).described("is empty"),
+        )
+        .assert_called(1);
+}
+
+#[cfg(feature = "predicates")]
+#[test]
+fn predicates_adapter() {
+    use mry::matchers::predicate;
+    use predicates::prelude::*;
+
+    let mut store = mry::new!(Store {});
+    store
+        .mock_get(predicate(predicate::str::starts_with("user-")))
+        .returns(Some("user".into()));
+
+    assert_eq!(store.get("user-1"), Some("user".into()));
+    store
+        .mock_get_all(predicate(predicate::function(|keys: &Vec<String>| {
+            keys.len() > 1
+        })))
+        .assert_called(0);
+}
+
+#[cfg(feature = "predicates")]
+#[test]
+#[should_panic(expected = "with (var.starts_with(\"admin-\"))")]
+fn predicates_description() {
+    use mry::matchers::predicate;
+    use predicates::prelude::*;
+
+    let mut store = mry::new!(Store {});
+    store
+        .mock_get(predicate(predicate::str::starts_with("admin-")))
+        .assert_called(1);
+}
+
+#[cfg(feature = "googletest")]
+#[test]
+fn googletest_adapter() {
+    use googletest::prelude::*;
+    use mry::matchers::googletest;
+
+    let mut store = mry::new!(Store {});
+    store.mock_get_all(googletest(len(eq(2)))).returns(1);
+
+    assert_eq!(store.get_all(&["a", "b"]), 1);
+}
@@ -0,0 +1,78 @@
+use mry::Any;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Event {
+    Click,
+    Key(char),
+}
+
+fn dispatch(events: Vec<Event>, handler: impl Fn(Event) -> bool) -> usize {
+    events
+        .into_iter()
+        .filter(|event| handler(event.clone()))
+        .count()
+}
+
+struct Ticker {
+    on_tick: Box<dyn FnMut(u32)>,
+}
+
+impl Ticker {
+    fn tick(&mut self, times: u32) {
+        for i in 0..times {
+            (self.on_tick)(i);
+        }
+    }
+}
+
+#[test]
+fn impl_fn_callback() {
+    let (handler, handle) = mry::mock_fn::<(Event,), bool>();
+    handle.mock(Event::Click).returns(true);
+    handle.mock(Any).returns(false);
+
+    assert_eq!(
+        dispatch(vec![Event::Click, Event::Key('a'), Event::Click], handler),
+        2
+    );
+    handle.mock(Event::Click).assert_called(2);
+    handle.mock(Event::Key('a')).assert_called(1);
+}
+
+#[test]
+fn boxed_fn_mut_callback() {
+    let (on_tick, handle) = mry::mock_fn::<(u32,), ()>();
+    handle.mock(Any).returns(());
+
+    let mut ticker = Ticker {
+        on_tick: Box::new(on_tick),
+    };
+    ticker.tick(3);
+
+    handle.mock(Any).assert_called(3);
+    handle.mock(2).assert_called(1);
+}
+
+#[test]
+fn behavior_with_arguments() {
+    let (score, handle) = mry::mock_fn::<(String, usize), usize>();
+    handle
+        .mock("bonus", Any)
+        .returns_with(|_, points: usize| points * 2);
+    handle.mock(Any, Any).returns_with(|_, points| points);
+
+    assert_eq!(score("bonus".into(), 10), 20);
+    assert_eq!(score("normal".into(), 10), 10);
+}
+
+#[test]
+fn shared_across_threads() {
+    let (handler, handle) = mry::mock_fn::<(Event,), bool>();
+    handle.mock(Any).returns(true);
+
+    std::thread::spawn(move || handler(Event::Click))
+        .join()
+        .unwrap();
+
+    handle.mock(Event::Click).assert_called(1);
+}
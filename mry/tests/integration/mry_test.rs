@@ -0,0 +1,98 @@
+use mry::Any;
+
+#[mry::mry]
+fn hello(count: usize) -> String {
+    "hello".repeat(count)
+}
+
+mod cat {
+    #[mry::mry]
+    #[derive(Default)]
+    pub struct Cat {}
+
+    #[mry::mry]
+    impl Cat {
+        pub fn meow(count: usize) -> String {
+            "meow".repeat(count)
+        }
+
+        pub async fn async_meow(count: usize) -> String {
+            "meow".repeat(count)
+        }
+    }
+}
+
+#[mry::mry]
+pub trait Named {
+    fn new(name: String) -> Self;
+}
+
+#[mry::test]
+fn locks_functions_automatically() {
+    mock_hello(Any).returns("mocked".to_string());
+    cat::Cat::mock_meow(Any).returns("mocked meow".to_string());
+
+    assert_eq!(hello(2), "mocked");
+    assert_eq!(cat::Cat::meow(2), "mocked meow");
+}
+
+#[mry::test]
+fn locks_mocks_in_macros() {
+    assert_eq!(
+        {
+            mock_hello(Any).returns("a".to_string());
+            hello(2)
+        },
+        "a"
+    );
+}
+
+#[mry::test]
+fn locks_trait_static_methods() {
+    MockNamed::mock_new(Any).returns(MockNamed::default());
+
+    MockNamed::new("Tama".to_string());
+}
+
+#[mry::test]
+#[mry::lock(hello)]
+fn works_with_explicit_lock() {
+    mock_hello(Any).returns("mocked".to_string());
+
+    assert_eq!(hello(2), "mocked");
+}
+
+#[mry::test]
+#[async_std::test]
+async fn async_test() {
+    cat::Cat::mock_async_meow(Any).returns("mocked".to_string());
+
+    assert_eq!(cat::Cat::async_meow(2).await, "mocked");
+}
+
+#[mry::test]
+fn without_static_mocks() {
+    assert_eq!(hello(2), "hellohello");
+}
+
+#[mry::test]
+fn mock_fn_is_not_locked() {
+    let (double, handle) = mry::mock_fn::<(u8,), u8>();
+    handle.mock(Any).returns_with(|value| value * 2);
+    mock_hello(Any).returns("mocked".to_string());
+
+    assert_eq!(double(2), 4);
+    assert_eq!(hello(2), "mocked");
+}
+
+fn mock_server() -> cat::Cat {
+    mry::new!(cat::Cat {})
+}
+
+#[mry::test(skip(mock_server))]
+fn skips_helpers_named_like_mocks() {
+    let _server = mock_server();
+    mock_hello(Any).returns("mocked".to_string());
+
+    assert_eq!(hello(2), "mocked");
+}
@@ -0,0 +1,63 @@
+use mry::Any;
+
+#[mry::mry]
+fn hello(count: usize) -> String {
+    "hello".repeat(count)
+}
+
+#[mry::mry]
+struct Cat {}
+
+#[mry::mry]
+impl Cat {
+    async fn meow(count: usize) -> String {
+        "meow".repeat(count)
+    }
+}
+
+#[mry::lock(hello)]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn spawned_tasks_use_static_mocks() {
+    mock_hello(Any).returns("mocked".into());
+
+    let tasks: Vec<_> = (0..16)
+        .map(|count| {
+            tokio::spawn(mry::with_static_mocks(async move {
+                tokio::task::yield_now().await;
+                let first = hello(count);
+                // May be resumed on another worker thread
+                tokio::task::yield_now().await;
+                (first, hello(count))
+            }))
+        })
+        .collect();
+
+    for task in tasks {
+        assert_eq!(
+            task.await.unwrap(),
+            ("mocked".to_string(), "mocked".to_string())
+        );
+    }
+    mock_hello(Any).assert_called(32);
+}
+
+#[mry::lock(Cat::meow)]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn async_static_method_across_awaits() {
+    Cat::mock_meow(Any).returns("mocked".into());
+
+    assert_eq!(Cat::meow(1).await, "mocked");
+    let spawned = tokio::spawn(mry::with_static_mocks(Cat::meow(2)));
+    assert_eq!(spawned.await.unwrap(), "mocked");
+
+    Cat::mock_meow(Any).assert_called(2);
+}
+
+#[mry::lock(hello)]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn spawned_task_without_scope_calls_real_impl() {
+    mock_hello(Any).returns("mocked".into());
+
+    assert_eq!(tokio::spawn(async { hello(1) }).await.unwrap(), "hello");
+    mock_hello(Any).assert_called(0);
+}
@@ -0,0 +1,93 @@
+use mry::Any;
+
+#[mry::mry]
+#[derive(Default, Clone, PartialEq)]
+struct Storage {
+    path: String,
+}
+
+#[mry::mry]
+impl Storage {
+    fn get(&self, key: usize) -> String {
+        format!("{}/{key}", self.path)
+    }
+}
+
+#[mry::mry]
+trait Repo {
+    fn find(&self, id: usize) -> Option<String>;
+}
+
+#[test]
+#[should_panic(expected = "mock not found for Storage::get (secondary)")]
+fn name_appears_in_mock_not_found() {
+    let mut primary = mry::new!("primary", Storage { path: "a".into() });
+    let mut secondary = mry::new!("secondary", Storage { path: "b".into() });
+    primary.mock_get(Any).returns("primary".into());
+    secondary.mock_get(1).returns("secondary".into());
+
+    assert_eq!(primary.get(2), "primary");
+    secondary.get(2);
+}
+
+#[test]
+#[should_panic(
+    expected = "Expected Storage::get (replica) to be called 1 times, but it was called 0 times"
+)]
+fn name_appears_in_assertion() {
+    let mut storage = Storage::default();
+    storage.mry.set_name("replica");
+
+    storage.mock_get(Any).assert_called(1);
+}
+
+#[test]
+#[should_panic(expected = "Storage::get (primary) was called more than once")]
+fn name_appears_in_returns_once() {
+    let mut storage = mry::new!("primary", Storage { path: "a".into() });
+    storage.mock_get(Any).returns_once("once".into());
+
+    storage.get(1);
+    storage.get(1);
+}
+
+#[test]
+#[should_panic(expected = "mock not found for Repo::find (users)")]
+fn named_trait_mock() {
+    let mut repo = MockRepo::named("users");
+    repo.mock_find(1).returns(None);
+
+    repo.find(2);
+}
+
+#[test]
+fn name_appears_in_debug() {
+    let repo = MockRepo::named("users");
+
+    assert_eq!(repo.mry.name(), Some("users".to_string()));
+    assert!(format!("{repo:?}").contains("name: \"users\""));
+}
+
+#[test]
+fn clone_shares_name() {
+    let storage = mry::new!("primary", Storage { path: "a".into() });
+
+    assert_eq!(storage.clone().mry.name(), Some("primary".to_string()));
+}
+
+#[test]
+fn named_without_rules_calls_real_impl() {
+    let storage = mry::new!("primary", Storage { path: "a".into() });
+
+    assert_eq!(storage.get(1), "a/1");
+}
+
+#[test]
+#[should_panic(expected = "mock not found for Storage::get (renamed)")]
+fn renamed_after_mock() {
+    let mut storage = mry::new!("primary", Storage { path: "a".into() });
+    storage.mock_get(1).returns("mocked".into());
+    storage.mry.set_name("renamed");
+
+    storage.get(2);
+}
@@ -0,0 +1,99 @@
+use std::{
+    borrow::Cow,
+    ffi::{CStr, CString, OsStr, OsString},
+    path::{Path, PathBuf},
+};
+
+#[mry::mry]
+struct Server {}
+
+struct Request<'a> {
+    path: &'a str,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct OwnedRequest {
+    path: String,
+}
+
+impl From<&Request<'_>> for OwnedRequest {
+    fn from(request: &Request<'_>) -> Self {
+        Self {
+            path: request.path.to_string(),
+        }
+    }
+}
+
+trait Handler {
+    fn name(&self) -> &str;
+}
+
+struct Index;
+
+impl Handler for Index {
+    fn name(&self) -> &str {
+        "index"
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct HandlerName(String);
+
+impl From<&dyn Handler> for HandlerName {
+    fn from(handler: &dyn Handler) -> Self {
+        Self(handler.name().to_string())
+    }
+}
+
+#[mry::mry(owned(Request<'_> => OwnedRequest, dyn Handler => HandlerName))]
+impl Server {
+    fn handle(&self, request: Request<'_>) -> u16 {
+        request.path.len() as u16
+    }
+
+    fn route(&self, request: &Request<'_>, handler: &dyn Handler) -> String {
+        format!("{} {}", request.path, handler.name())
+    }
+
+    fn open(&self, path: &Path, name: &OsStr, raw: &CStr) -> bool {
+        path.exists() && !name.is_empty() && !raw.is_empty()
+    }
+
+    fn title(&self, title: Cow<'_, str>) -> String {
+        title.into_owned()
+    }
+}
+
+#[test]
+fn declared_conversion() {
+    let mut server = mry::new!(Server {});
+    server
+        .mock_handle(OwnedRequest { path: "/".into() })
+        .returns(200);
+    server
+        .mock_route(mry::Any, HandlerName("index".into()))
+        .returns("mocked".into());
+
+    assert_eq!(server.handle(Request { path: "/" }), 200);
+    assert_eq!(server.route(&Request { path: "/" }, &Index), "mocked");
+    server
+        .mock_route(OwnedRequest { path: "/".into() }, mry::Any)
+        .assert_called(1);
+}
+
+#[test]
+fn unsized_references() {
+    let mut server = mry::new!(Server {});
+    server
+        .mock_open(
+            PathBuf::from("a.txt"),
+            OsString::from("a"),
+            CString::new("a").unwrap(),
+        )
+        .returns(true);
+    server.mock_title("cat".to_string()).returns("meow".into());
+
+    assert!(server.open(Path::new("a.txt"), OsStr::new("a"), c"a"));
+    assert_eq!(server.title(Cow::Borrowed("cat")), "meow");
+    assert_eq!(server.title(Cow::Owned("cat".into())), "meow");
+}
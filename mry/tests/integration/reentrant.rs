@@ -0,0 +1,145 @@
+use mry::Any;
+
+#[mry::mry]
+#[derive(Default, Clone)]
+struct Cat {
+    name: String,
+}
+
+#[mry::mry]
+impl Cat {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn greet(&self) -> String {
+        format!("I'm {}", self.name())
+    }
+
+    fn count(&self, n: usize) -> usize {
+        n
+    }
+
+    fn name_static() -> String {
+        "Tama".into()
+    }
+
+    fn greet_static() -> String {
+        format!("I'm {}", Cat::name_static())
+    }
+}
+
+#[mry::mry]
+trait Counter {
+    fn count(&self, n: usize) -> usize;
+}
+
+#[test]
+fn behavior_calls_the_same_object() {
+    let mut cat = Cat::default();
+    cat.mock_name().returns("Mike".into());
+    // Shares the mocks with `cat`
+    let shared = cat.clone();
+    cat.mock_greet()
+        .returns_with(move || format!("Hello, {}", shared.name()));
+
+    assert_eq!(cat.greet(), "Hello, Mike");
+    cat.mock_name().assert_called(1);
+    cat.mock_greet().assert_called(1);
+}
+
+#[test]
+fn recursive_call_matches_other_rules() {
+    let mut cat = Cat::default();
+    cat.mock_count(0).returns(100);
+    let shared = cat.clone();
+    cat.mock_count(Any)
+        .returns_with(move |n| shared.count(n - 1) + 1);
+
+    assert_eq!(cat.count(1), 101);
+    cat.mock_count(1).assert_called(1);
+    cat.mock_count(0).assert_called(1);
+}
+
+#[test]
+#[should_panic(expected = "behavior for Cat::count is already running on this thread")]
+fn recursive_call_to_the_running_rule_panics() {
+    let mut cat = Cat::default();
+    cat.mock_count(0).returns(100);
+    let shared = cat.clone();
+    cat.mock_count(Any)
+        .returns_with(move |n| shared.count(n - 1) * 10);
+
+    // The running rule matches 2 before the rule of 0.
+    cat.count(3);
+}
+
+#[test]
+#[should_panic(expected = "behavior for Counter::count is already running on this thread")]
+fn recursive_call_to_the_running_rule_of_trait_mock_panics() {
+    let mut counter = MockCounter::default();
+    let shared = MockCounter {
+        mry: counter.mry.share(),
+    };
+    counter
+        .mock_count(Any)
+        .returns_with(move |n| if n == 0 { 1 } else { shared.count(n - 1) * n });
+
+    counter.count(3);
+}
+
+#[test]
+#[should_panic(expected = "fallback_with for Cat::count is already running on this thread")]
+fn recursive_call_to_the_running_fallback_panics() {
+    let mut cat = Cat::default();
+    let shared = Cat {
+        mry: cat.mry.share(),
+        ..cat.clone()
+    };
+    cat.mry.fallback_with(move || shared.count(1));
+
+    cat.count(2);
+}
+
+#[test]
+fn fallback_with_calls_the_same_object() {
+    let mut cat = Cat::default();
+    cat.mock_name().returns("Mike".into());
+    let shared = cat.clone();
+    cat.mry.fallback_with(move || shared.name().len());
+
+    assert_eq!(cat.count(2), 4);
+    cat.mock_name().assert_called(1);
+}
+
+#[test]
+#[mry::lock(Cat::name_static, Cat::greet_static)]
+fn static_behavior_calls_another_static_mock() {
+    Cat::mock_name_static().returns("Mike".into());
+    Cat::mock_greet_static().returns_with(|| format!("Hello, {}", Cat::name_static()));
+
+    assert_eq!(Cat::greet_static(), "Hello, Mike");
+    Cat::mock_name_static().assert_called(1);
+}
+
+#[test]
+fn behavior_does_not_block_other_calls() {
+    let mut cat = Cat::default();
+    let (entered_sender, entered) = std::sync::mpsc::channel();
+    let (resume, resume_receiver) = std::sync::mpsc::channel();
+    cat.mock_count(1).returns_with(move |n| {
+        entered_sender.send(()).unwrap();
+        resume_receiver.recv().unwrap();
+        n
+    });
+    cat.mock_count(2).returns(2);
+
+    let shared = cat.clone();
+    let handle = std::thread::spawn(move || shared.count(1));
+    entered.recv().unwrap();
+    // The mocks are not locked while the behavior on the other thread waits.
+    assert_eq!(cat.count(2), 2);
+    resume.send(()).unwrap();
+
+    assert_eq!(handle.join().unwrap(), 1);
+}
@@ -0,0 +1,116 @@
+#[mry::mry]
+#[derive(Default)]
+struct Cat {
+    first: String,
+    last: String,
+    meows: usize,
+}
+
+#[mry::mry]
+impl Cat {
+    fn first_name(&self) -> String {
+        self.first.clone()
+    }
+
+    fn full_name(&self) -> String {
+        format!("{} {}", self.first, self.last)
+    }
+
+    fn greet(&self, greeting: &str) -> String {
+        format!("{greeting}, {}", self.full_name())
+    }
+
+    fn meow(&mut self, count: usize) -> usize {
+        self.meows += count;
+        self.meows
+    }
+
+    async fn async_name(&self) -> String {
+        self.first.clone()
+    }
+}
+
+#[mry::mry]
+trait Named {
+    fn name(&self) -> String;
+}
+
+#[test]
+fn reads_fields() {
+    let mut cat = mry::new!(Cat {
+        first: "Tama".into(),
+        last: "Neko".into(),
+        meows: 0,
+    });
+    cat.mock_full_name()
+        .returns_with_self(|this: &Cat| format!("{}, {}", this.last, this.first));
+
+    assert_eq!(cat.full_name(), "Neko, Tama");
+}
+
+#[test]
+fn delegates_to_other_methods() {
+    let mut cat = mry::new!(Cat {
+        first: "Tama".into(),
+        last: "Neko".into(),
+        meows: 0,
+    });
+    cat.mock_first_name().returns("Mike".to_string());
+    cat.mock_full_name()
+        .returns_with_self(|this: &Cat| format!("{} {}", this.first_name(), this.last));
+    cat.mock_greet(mry::Any)
+        .returns_with_self(|this: &Cat, greeting: String| {
+            format!("{greeting}! {}", this.full_name())
+        });
+
+    assert_eq!(cat.greet("Hi"), "Hi! Mike Neko");
+    cat.mock_first_name().assert_called(1);
+}
+
+#[test]
+fn mutates_receiver() {
+    let mut cat = Cat::default();
+    cat.mock_meow(mry::Any)
+        .returns_with_self_mut(|this: &mut Cat, count| {
+            this.meows += count * 10;
+            this.meows
+        });
+
+    assert_eq!(cat.meow(1), 10);
+    assert_eq!(cat.meow(2), 30);
+}
+
+#[tokio::test]
+async fn async_method() {
+    let mut cat = mry::new!(Cat {
+        first: "Tama".into(),
+        last: "Neko".into(),
+        meows: 0,
+    });
+    cat.mock_async_name()
+        .returns_with_self(|this: &Cat| this.last.clone());
+
+    assert_eq!(cat.async_name().await, "Neko");
+}
+
+#[test]
+fn trait_mock() {
+    let mut named = MockNamed::named("cat");
+    named
+        .mock_name()
+        .returns_with_self(|this: &MockNamed| this.mry.name().unwrap());
+
+    assert_eq!(named.name(), "cat");
+}
+
+#[test]
+#[should_panic(
+    expected = "`returns_with_self_mut` is used for Cat::full_name, which has no `&mut self` receiver"
+)]
+fn mut_behavior_for_shared_receiver() {
+    let mut cat = Cat::default();
+    cat.mock_full_name()
+        .returns_with_self_mut(|this: &mut Cat| this.first.clone());
+
+    cat.full_name();
+}
@@ -0,0 +1,54 @@
+use std::sync::{
+    atomic::{AtomicU8, Ordering},
+    Arc,
+};
+
+#[mry::mry]
+trait Counter {
+    fn get(&self) -> u8;
+    fn set(&self, value: u8);
+}
+
+fn increment(counter: &impl Counter) {
+    let value = counter.get();
+    counter.set(value + 1);
+}
+
+fn check_increments() {
+    let value = Arc::new(AtomicU8::new(0));
+    let mut counter = MockCounter::default();
+    counter.mock_get().returns_with({
+        let value = value.clone();
+        move || value.load(Ordering::SeqCst)
+    });
+    counter.mock_set(mry::Any).returns_with({
+        let value = value.clone();
+        move |new| value.store(new, Ordering::SeqCst)
+    });
+    let counter = Arc::new(counter);
+
+    let thread = shuttle::thread::spawn({
+        let counter = counter.clone();
+        move || increment(&*counter)
+    });
+    increment(&*counter);
+    thread.join().unwrap();
+
+    let mut counter = Arc::into_inner(counter).unwrap();
+    counter.mock_get().assert_called(2);
+    assert_eq!(value.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+#[should_panic(expected = "left: 1")]
+fn shuttle_finds_lost_update() {
+    shuttle::check_dfs(check_increments, None);
+}
+
+#[test]
+fn mocks_outside_shuttle() {
+    let mut counter = MockCounter::default();
+    counter.mock_get().returns(1);
+
+    assert_eq!(counter.get(), 1);
+}
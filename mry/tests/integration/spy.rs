@@ -0,0 +1,75 @@
+use mry::Any;
+
+#[mry::mry]
+#[derive(Default, Clone)]
+struct Cat {
+    name: String,
+}
+
+#[mry::mry]
+impl Cat {
+    fn meow(&self, count: usize) -> String {
+        format!("{}: {}", self.name, self.meow_single().repeat(count))
+    }
+
+    fn meow_single(&self) -> String {
+        "meow".into()
+    }
+}
+
+#[mry::mry]
+trait Named {
+    fn name(&self) -> String;
+    fn greeting(&self) -> String {
+        format!("Hello, {}", self.name())
+    }
+}
+
+#[test]
+fn spy_macro_records_real_calls() {
+    let mut cat = mry::spy!(Cat {
+        name: "Tama".into()
+    });
+
+    assert_eq!(cat.meow(2), "Tama: meowmeow");
+
+    cat.mock_meow(2).assert_called(1);
+    cat.mock_meow_single().assert_called(1);
+}
+
+#[test]
+fn spy_all_then_override() {
+    let mut cat = Cat {
+        name: "Tama".into(),
+        ..Default::default()
+    };
+    cat.mry.spy_all();
+    cat.mock_meow_single().returns("nya".to_string());
+
+    assert_eq!(cat.meow(1), "Tama: nya");
+    assert_eq!(cat.meow(2), "Tama: nyanya");
+
+    cat.mock_meow(Any).assert_called(2);
+    cat.mock_meow(1).assert_called(1);
+    cat.mock_meow_single().assert_called(2);
+}
+
+#[test]
+fn spy_trait_default_method() {
+    let mut named = MockNamed::default();
+    named.mry.spy_all();
+    named.mock_name().returns("Tama".to_string());
+
+    assert_eq!(named.greeting(), "Hello, Tama");
+
+    named.mock_greeting().assert_called(1);
+}
+
+#[test]
+#[should_panic(expected = "mock not found for Named")]
+fn spy_trait_required_method() {
+    let mut named = MockNamed::default();
+    named.mry.spy_all();
+
+    named.name();
+}
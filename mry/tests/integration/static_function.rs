@@ -78,3 +78,17 @@ fn hello_returns() {
 #[should_panic(expected = "hello is locked but no used.")]
 #[test]
 fn hello_not_used() {}
+
+#[mry::lock(hello, Cat::meow)]
+#[test]
+fn matcher_calls_real_impl_of_static_mock() {
+    Cat::mock_meow(Any).returns("Called".to_string());
+    mock_hello(mry::ArgMatcher::Fn(Box::new(|count: &usize| {
+        Cat::meow(*count) == "meow"
+    })))
+    .returns("matched".to_string());
+
+    assert_eq!(hello(1), "matched");
+    assert_eq!(Cat::meow(1), "Called");
+    Cat::mock_meow(Any).assert_called(1);
+}
@@ -0,0 +1,129 @@
+use mry::Items;
+
+#[mry::mry]
+struct Feed {}
+
+#[mry::mry]
+impl Feed {
+    fn numbers(&self, count: usize) -> impl Iterator<Item = usize> + Send {
+        0..count
+    }
+}
+
+#[mry::mry]
+trait Source {
+    fn values(&self) -> impl Iterator<Item = u8> + Send;
+}
+
+#[test]
+fn returns_stream_as_iterator() {
+    let mut feed = mry::new!(Feed {});
+    feed.mock_numbers(mry::Any).returns_stream([3, 1, 2]);
+
+    assert_eq!(feed.numbers(1).collect::<Vec<_>>(), [3, 1, 2]);
+    assert_eq!(feed.numbers(1).collect::<Vec<_>>(), [3, 1, 2]);
+}
+
+#[test]
+fn returns_stream_with_as_iterator() {
+    let mut feed = mry::new!(Feed {});
+    feed.mock_numbers(mry::Any)
+        .returns_stream_with(|count: usize| (0..count).rev());
+
+    assert_eq!(feed.numbers(3).collect::<Vec<_>>(), [2, 1, 0]);
+}
+
+#[test]
+fn returns_stream_for_trait() {
+    let mut source = MockSource::default();
+    source.mock_values().returns_stream([1, 2]);
+
+    assert_eq!(source.values().collect::<Vec<_>>(), [1, 2]);
+}
+
+#[test]
+fn real_impl_of_impl_trait_output() {
+    let mut feed = mry::new!(Feed {});
+    feed.mock_numbers(mry::Any).calls_real_impl();
+
+    assert_eq!(feed.numbers(2).collect::<Vec<_>>(), [0, 1]);
+}
+
+#[test]
+#[should_panic(expected = "cannot wait for more items")]
+fn never_ending_iterator_panics() {
+    let mut feed = mry::new!(Feed {});
+    feed.mock_numbers(mry::Any)
+        .returns_stream(Items::new([1]).never_ending());
+
+    feed.numbers(1).for_each(drop);
+}
+
+#[cfg(feature = "futures")]
+mod futures_stream {
+    use std::{
+        pin::pin,
+        task::{Context, Poll, Waker},
+    };
+
+    use futures::{executor::block_on, Stream, StreamExt};
+
+    use super::*;
+
+    #[mry::mry]
+    struct Feed {}
+
+    #[mry::mry]
+    impl Feed {
+        fn events(&self) -> impl Stream<Item = Result<u8, String>> + Send {
+            futures::stream::iter([Ok(1)])
+        }
+
+        fn boxed(&self) -> futures::stream::BoxStream<'static, u8> {
+            Box::pin(futures::stream::iter([1]))
+        }
+    }
+
+    fn poll<S: Stream + Unpin>(stream: &mut S) -> Poll<Option<S::Item>> {
+        stream.poll_next_unpin(&mut Context::from_waker(Waker::noop()))
+    }
+
+    #[test]
+    fn returns_stream() {
+        let mut feed = mry::new!(Feed {});
+        feed.mock_events()
+            .returns_stream(Items::new([Ok(1), Ok(2)]).ending_with_error("closed".into()));
+        feed.mock_boxed().returns_stream([4, 5]);
+
+        assert_eq!(
+            block_on(feed.events().collect::<Vec<_>>()),
+            [Ok(1), Ok(2), Err("closed".to_string())]
+        );
+        assert_eq!(block_on(feed.boxed().collect::<Vec<_>>()), [4, 5]);
+    }
+
+    #[test]
+    fn pending_between() {
+        let mut feed = mry::new!(Feed {});
+        feed.mock_boxed()
+            .returns_stream(Items::new([1, 2]).pending_between());
+        let mut stream = feed.boxed();
+
+        assert_eq!(poll(&mut stream), Poll::Ready(Some(1)));
+        assert_eq!(poll(&mut stream), Poll::Pending);
+        assert_eq!(poll(&mut stream), Poll::Ready(Some(2)));
+        assert_eq!(poll(&mut stream), Poll::Ready(None));
+    }
+
+    #[test]
+    fn never_ending() {
+        let mut feed = mry::new!(Feed {});
+        feed.mock_events()
+            .returns_stream(Items::new([Ok(1)]).never_ending());
+        let mut stream = pin!(feed.events());
+
+        assert_eq!(poll(&mut stream), Poll::Ready(Some(Ok(1))));
+        assert_eq!(poll(&mut stream), Poll::Pending);
+        assert_eq!(poll(&mut stream), Poll::Pending);
+    }
+}
@@ -0,0 +1,61 @@
+use mry::Any;
+
+#[mry::mry]
+pub trait Named {
+    fn name(&self) -> String;
+    fn greeting(&self) -> String {
+        format!("Hello, {}", self.name())
+    }
+}
+
+mod base {
+    #[mry::mry]
+    pub trait Base {
+        fn id(&self) -> usize;
+    }
+}
+
+#[mry::mry(supertraits(Named, base::Base))]
+pub trait Service: Clone + std::fmt::Debug + Named + base::Base {
+    fn serve(&self, count: usize) -> String;
+}
+
+fn describe(service: &impl Service) -> String {
+    format!("{}#{}: {}", service.name(), service.id(), service.serve(2))
+}
+
+#[test]
+fn shares_mry_with_supertraits() {
+    let mut service = MockService::default();
+    service.mock_name().returns("tama".to_string());
+    service.mock_id().returns(3);
+    service.mock_serve(Any).returns("meow".to_string());
+
+    assert_eq!(describe(&service), "tama#3: meow");
+
+    service.mock_name().assert_called(1);
+    service.mock_serve(2).assert_called(1);
+}
+
+#[test]
+fn supertrait_default_impl() {
+    let mut service = MockService::default();
+    service.mock_name().returns("tama".to_string());
+    service.mock_greeting().calls_real_impl();
+
+    assert_eq!(service.greeting(), "Hello, tama");
+}
+
+#[test]
+fn clone_shares_supertrait_mocks() {
+    let mut service = MockService::default();
+    service.mock_name().returns("tama".to_string());
+
+    assert_eq!(service.clone().name(), "tama");
+}
+
+#[test]
+#[should_panic(expected = "mock not found for Named")]
+fn no_mock() {
+    MockService::default().name();
+}
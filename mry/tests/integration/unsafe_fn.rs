@@ -0,0 +1,65 @@
+use mry::Any;
+
+#[mry::mry]
+#[derive(Default)]
+struct Cat {
+    name: String,
+}
+
+#[mry::mry(skip_fns(max_lives))]
+impl Cat {
+    unsafe fn name_at(&self, index: usize) -> u8 {
+        *self.name.as_bytes().get_unchecked(index)
+    }
+
+    extern "C" fn lives(&self) -> usize {
+        9
+    }
+
+    const fn max_lives() -> usize {
+        9
+    }
+}
+
+#[mry::mry]
+unsafe fn read(ptr: *const u8) -> u8 {
+    *ptr
+}
+
+const MAX_LIVES: usize = Cat::max_lives();
+
+#[test]
+fn unsafe_method() {
+    let mut cat = Cat {
+        name: "Tama".into(),
+        ..Default::default()
+    };
+
+    cat.mock_name_at(0).returns(b'M');
+    cat.mock_name_at(1).calls_real_impl();
+
+    assert_eq!(unsafe { cat.name_at(0) }, b'M');
+    assert_eq!(unsafe { cat.name_at(1) }, b'a');
+}
+
+#[test]
+fn extern_method() {
+    let mut cat = Cat::default();
+
+    cat.mock_lives().returns(1);
+
+    assert_eq!(cat.lives(), 1);
+}
+
+#[test]
+fn skipped_const_fn_stays_const() {
+    assert_eq!(MAX_LIVES, 9);
+}
+
+#[test]
+#[mry::lock(read)]
+fn unsafe_function() {
+    mock_read(Any).returns(3);
+
+    assert_eq!(unsafe { read(&1) }, 3);
+}
@@ -0,0 +1,37 @@
+// A separate test binary, since `MRY_LOCK_TIMEOUT` is read by every lock in the process.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+static LOCKED: AtomicBool = AtomicBool::new(false);
+
+#[mry::mry]
+fn hello() -> String {
+    "hello".to_string()
+}
+
+#[mry::lock(hello)]
+fn hold_lock() {
+    LOCKED.store(true, Ordering::SeqCst);
+    thread::sleep(Duration::from_secs(5));
+}
+
+#[mry::lock(hello)]
+fn wait_for_lock() {}
+
+#[test]
+#[should_panic(
+    expected = "timed out after 1s waiting for the lock of `hello` held by `holder_test`. Set MRY_LOCK_TIMEOUT to wait longer"
+)]
+fn timeout_names_holder() {
+    std::env::set_var(mry::LOCK_TIMEOUT_ENV, "1");
+    thread::Builder::new()
+        .name("holder_test".into())
+        .spawn(hold_lock)
+        .unwrap();
+    while !LOCKED.load(Ordering::SeqCst) {
+        thread::yield_now();
+    }
+
+    wait_for_lock();
+}
@@ -0,0 +1,35 @@
+// A separate test binary, since the panic hook is shared by every test in the process.
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Mutex;
+
+static LOCATION: Mutex<Option<(String, u32)>> = Mutex::new(None);
+
+#[mry::mry]
+#[derive(Default)]
+struct Cat {}
+
+#[mry::mry]
+impl Cat {
+    fn meow(&self, count: usize) -> String {
+        "meow".repeat(count)
+    }
+}
+
+#[test]
+fn mock_not_found_points_at_the_call() {
+    let mut cat = Cat::default();
+    cat.mock_meow(1).returns("mocked".into());
+
+    panic::set_hook(Box::new(|info| {
+        let location = info.location().unwrap();
+        *LOCATION.lock().unwrap() = Some((location.file().to_string(), location.line()));
+    }));
+    let line = line!() + 1;
+    let result = panic::catch_unwind(AssertUnwindSafe(|| cat.meow(2)));
+    let _ = panic::take_hook();
+
+    assert!(result.is_err());
+    let (file, panicked_line) = LOCATION.lock().unwrap().take().unwrap();
+    assert!(file.ends_with("panic_location.rs"), "{file}");
+    assert_eq!(panicked_line, line);
+}
@@ -36,3 +36,45 @@ fn serde() {
         })
     );
 }
+
+#[cfg(test)]
+mod json_subset {
+    use mry::matchers::json_subset;
+    use serde::Serialize;
+    use serde_json::json;
+
+    #[derive(Clone, Serialize)]
+    struct User {
+        id: u64,
+        name: String,
+        updated_at: u64,
+    }
+
+    #[mry::mry]
+    #[derive(Default)]
+    struct Repository {}
+
+    #[mry::mry]
+    impl Repository {
+        fn save(&self, user: User) -> bool {
+            user.id > 0
+        }
+    }
+
+    #[test]
+    fn matches_fields() {
+        let mut repository = mry::new!(Repository {});
+        repository
+            .mock_save(json_subset(json!({"id": 5})))
+            .returns(false);
+
+        assert!(!repository.save(User {
+            id: 5,
+            name: "Tama".into(),
+            updated_at: 1,
+        }));
+        repository
+            .mock_save(json_subset(json!({"name": "Tama"})))
+            .assert_called(1);
+    }
+}
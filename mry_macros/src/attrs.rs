@@ -1,5 +1,13 @@
 use darling::{ast::NestedMeta, FromMeta};
-use syn::{visit::Visit, Meta};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    visit::Visit,
+    visit_mut::{self, VisitMut},
+    Meta, Token,
+};
 
 #[derive(FromMeta, Default)]
 pub(crate) struct MryAttr {
@@ -7,10 +15,48 @@ pub(crate) struct MryAttr {
     pub non_send: Option<NotSend>,
     pub skip_args: Option<Skip>,
     pub skip_fns: Option<Skip>,
+    pub supertraits: Option<Supertraits>,
+    pub fallback: Option<FallbackPolicy>,
+    pub clone: Option<ClonePolicy>,
+    pub by_ref: Option<ByRef>,
+    pub by_ref_fns: Option<Skip>,
+    pub arg_into: Option<ArgInto>,
+    pub owned: Option<Owned>,
+    pub handle: Option<Handle>,
+    /// The attribute as written, forwarded to the subtraits of a mocked trait
+    #[darling(skip)]
+    pub tokens: TokenStream,
 }
 
 pub(crate) struct NotSend(pub Vec<syn::Path>);
 pub(crate) struct Skip(pub Vec<syn::Path>);
+pub(crate) struct Supertraits(pub Vec<syn::Path>);
+/// Types matched by reference, with optional projections recorded instead of the arguments
+pub(crate) struct ByRef(pub Vec<(syn::Path, Option<syn::ExprClosure>)>);
+
+/// Arguments recorded as the given types like `arg_into(body = Vec<u8>)`
+pub(crate) struct ArgInto(pub Vec<(syn::Ident, syn::Type)>);
+
+/// Types recorded as their owned types like `owned(Request<'_> => OwnedRequest)`
+pub(crate) struct Owned(pub Vec<(syn::Type, syn::Type)>);
+
+/// The accessor of a handle to the mocks like `handle` or `handle = "controller"`
+pub(crate) struct Handle(pub syn::Ident);
+
+/// What a mocked function does when no rule matches a call
+pub(crate) enum FallbackPolicy {
+    Panic,
+    CallsRealImpl,
+    Default,
+}
+
+/// What the `Clone` of a mock object does with its rules and calls
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum ClonePolicy {
+    Share,
+    Fresh,
+    Deep,
+}
 
 impl FromMeta for NotSend {
     fn from_list(list: &[NestedMeta]) -> darling::Result<Self> {
@@ -40,7 +86,178 @@ impl FromMeta for Skip {
     }
 }
 
+impl FromMeta for Supertraits {
+    fn from_list(list: &[NestedMeta]) -> darling::Result<Self> {
+        list.iter()
+            .map(|meta| match meta {
+                NestedMeta::Meta(Meta::Path(path)) => Ok(path.clone()),
+                _ => Err(darling::Error::custom(
+                    "expected a list of traits like supertraits(A, B)",
+                )),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Supertraits)
+    }
+}
+
+impl FromMeta for ByRef {
+    fn from_list(list: &[NestedMeta]) -> darling::Result<Self> {
+        list.iter()
+            .map(|meta| match meta {
+                NestedMeta::Meta(Meta::Path(path)) => Ok((path.clone(), None)),
+                NestedMeta::Meta(Meta::NameValue(syn::MetaNameValue {
+                    path,
+                    value: syn::Expr::Closure(projection),
+                    ..
+                })) if matches!(projection.output, syn::ReturnType::Type(..)) => {
+                    Ok((path.clone(), Some(projection.clone())))
+                }
+                _ => Err(darling::Error::custom(
+                    "expected a list of types like by_ref(T, U = |u: &U| -> String { u.to_string() })",
+                )),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(ByRef)
+    }
+}
+
+impl FromMeta for ArgInto {
+    fn from_meta(item: &Meta) -> darling::Result<Self> {
+        struct Item(syn::Ident, syn::Type);
+        impl Parse for Item {
+            fn parse(input: ParseStream) -> syn::Result<Self> {
+                let ident = input.parse()?;
+                input.parse::<Token![=]>()?;
+                Ok(Item(ident, input.parse()?))
+            }
+        }
+        let Meta::List(list) = item else {
+            return Err(darling::Error::custom(
+                "expected a list of arguments like arg_into(body = Vec<u8>)",
+            ));
+        };
+        let items = list
+            .parse_args_with(Punctuated::<Item, Token![,]>::parse_terminated)
+            .map_err(darling::Error::from)?;
+        Ok(ArgInto(
+            items
+                .into_iter()
+                .map(|Item(ident, ty)| (ident, ty))
+                .collect(),
+        ))
+    }
+}
+
+impl FromMeta for Owned {
+    fn from_meta(item: &Meta) -> darling::Result<Self> {
+        struct Item(syn::Type, syn::Type);
+        impl Parse for Item {
+            fn parse(input: ParseStream) -> syn::Result<Self> {
+                let mut borrowed = input.parse()?;
+                input.parse::<Token![=>]>()?;
+                EraseLifetimes.visit_type_mut(&mut borrowed);
+                Ok(Item(borrowed, input.parse()?))
+            }
+        }
+        let Meta::List(list) = item else {
+            return Err(darling::Error::custom(
+                "expected a list of conversions like owned(Request<'_> => OwnedRequest)",
+            ));
+        };
+        let items = list
+            .parse_args_with(Punctuated::<Item, Token![,]>::parse_terminated)
+            .map_err(darling::Error::from)?;
+        Ok(Owned(
+            items
+                .into_iter()
+                .map(|Item(borrowed, owned)| (borrowed, owned))
+                .collect(),
+        ))
+    }
+}
+
+impl FromMeta for FallbackPolicy {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        match value {
+            "panic" => Ok(FallbackPolicy::Panic),
+            "real" => Ok(FallbackPolicy::CallsRealImpl),
+            "default" => Ok(FallbackPolicy::Default),
+            _ => Err(darling::Error::unknown_value(value)),
+        }
+    }
+}
+
+impl ToTokens for FallbackPolicy {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(match self {
+            FallbackPolicy::Panic => quote![mry::Fallback::Panic],
+            FallbackPolicy::CallsRealImpl => quote![mry::Fallback::CallsRealImpl],
+            FallbackPolicy::Default => quote![mry::Fallback::Default],
+        });
+    }
+}
+
+impl FromMeta for Handle {
+    fn from_word() -> darling::Result<Self> {
+        Ok(Handle(format_ident!("handle")))
+    }
+
+    fn from_string(value: &str) -> darling::Result<Self> {
+        syn::parse_str(value).map(Handle).map_err(|_| {
+            darling::Error::custom("expected a method name like handle = \"controller\"")
+        })
+    }
+}
+
+impl Handle {
+    /// The type of the handle of `mock`, like `CatHandle` for `handle` or `CatController` for
+    /// `controller`
+    pub fn type_ident(&self, mock: &syn::Ident) -> syn::Ident {
+        let pascal_case: String = self
+            .0
+            .to_string()
+            .split('_')
+            .flat_map(|word| {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_uppercase())
+                    .into_iter()
+                    .chain(chars)
+            })
+            .collect();
+        format_ident!("{}{}", mock, pascal_case)
+    }
+}
+
+impl FromMeta for ClonePolicy {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        match value {
+            "share" => Ok(ClonePolicy::Share),
+            "fresh" => Ok(ClonePolicy::Fresh),
+            "deep" => Ok(ClonePolicy::Deep),
+            _ => Err(darling::Error::unknown_value(value)),
+        }
+    }
+}
+
+impl ClonePolicy {
+    /// The `mry` field of a clone of `self`
+    pub fn mry(&self) -> TokenStream {
+        match self {
+            ClonePolicy::Share => quote![self.mry.share()],
+            ClonePolicy::Fresh => quote![Default::default()],
+            ClonePolicy::Deep => quote![self.mry.fork()],
+        }
+    }
+}
+
 impl MryAttr {
+    /// The policy of a `Clone` implemented by mry instead of derived
+    pub fn custom_clone(&self) -> Option<ClonePolicy> {
+        self.clone
+    }
+
     pub fn test_non_send(&self, ty: &syn::Type) -> bool {
         let Some(non_send) = &self.non_send else {
             return false;
@@ -65,6 +282,53 @@ impl MryAttr {
         visitor.found
     }
 
+    /// Returns `Some` with the projection if the argument is matched by reference
+    pub fn by_ref_arg(
+        &self,
+        method_name: &syn::Ident,
+        ty: &syn::Type,
+    ) -> Option<Option<&syn::ExprClosure>> {
+        let by_ref = self
+            .by_ref
+            .iter()
+            .flat_map(|by_ref| &by_ref.0)
+            .find(|(path, _)| {
+                let mut visitor = TypeVisitor {
+                    paths: std::slice::from_ref(path),
+                    found: false,
+                };
+                visitor.visit_type(ty);
+                visitor.found
+            });
+        if let Some((_, projection)) = by_ref {
+            return Some(projection.as_ref());
+        }
+        self.by_ref_fns
+            .as_ref()
+            .is_some_and(|fns| fns.0.iter().any(|p| p.is_ident(method_name)))
+            .then_some(None)
+    }
+
+    /// Returns the type the argument is recorded as
+    pub fn arg_into(&self, arg_name: &syn::Ident) -> Option<&syn::Type> {
+        self.arg_into
+            .iter()
+            .flat_map(|arg_into| &arg_into.0)
+            .find(|(ident, _)| ident == arg_name)
+            .map(|(_, ty)| ty)
+    }
+
+    /// Returns the owned type declared for the type with `owned(..)`, ignoring lifetimes
+    pub fn owned(&self, ty: &syn::Type) -> Option<&syn::Type> {
+        let mut ty = ty.clone();
+        EraseLifetimes.visit_type_mut(&mut ty);
+        self.owned
+            .iter()
+            .flat_map(|owned| &owned.0)
+            .find(|(borrowed, _)| *borrowed == ty)
+            .map(|(_, owned)| owned)
+    }
+
     pub fn should_skip_method(&self, method_name: &syn::Ident) -> bool {
         if let Some(skip) = &self.skip_fns {
             if skip.0.iter().any(|p| p.is_ident(method_name)) {
@@ -76,7 +340,7 @@ impl MryAttr {
 }
 
 struct TypeVisitor<'a> {
-    paths: &'a Vec<syn::Path>,
+    paths: &'a [syn::Path],
     found: bool,
 }
 impl Visit<'_> for TypeVisitor<'_> {
@@ -102,6 +366,46 @@ impl Visit<'_> for TypeVisitor<'_> {
     }
 }
 
+/// Removes lifetimes so that `Request<'a>`, `Request<'_>`, and `Request` are the same
+struct EraseLifetimes;
+impl VisitMut for EraseLifetimes {
+    fn visit_angle_bracketed_generic_arguments_mut(
+        &mut self,
+        args: &mut syn::AngleBracketedGenericArguments,
+    ) {
+        args.args = Punctuated::from_iter(
+            args.args
+                .clone()
+                .into_iter()
+                .filter(|arg| !matches!(arg, syn::GenericArgument::Lifetime(_))),
+        );
+        visit_mut::visit_angle_bracketed_generic_arguments_mut(self, args);
+    }
+
+    fn visit_path_arguments_mut(&mut self, args: &mut syn::PathArguments) {
+        visit_mut::visit_path_arguments_mut(self, args);
+        if matches!(args, syn::PathArguments::AngleBracketed(angle) if angle.args.is_empty()) {
+            *args = syn::PathArguments::None;
+        }
+    }
+
+    fn visit_type_reference_mut(&mut self, reference: &mut syn::TypeReference) {
+        reference.lifetime = None;
+        visit_mut::visit_type_reference_mut(self, reference);
+    }
+
+    fn visit_type_trait_object_mut(&mut self, object: &mut syn::TypeTraitObject) {
+        object.bounds = Punctuated::from_iter(
+            object
+                .bounds
+                .clone()
+                .into_iter()
+                .filter(|bound| !matches!(bound, syn::TypeParamBound::Lifetime(_))),
+        );
+        visit_mut::visit_type_trait_object_mut(self, object);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use syn::parse_quote;
@@ -163,6 +467,171 @@ mod tests {
         assert!(!attr.test_non_send(&parse_quote!(String)));
     }
 
+    #[test]
+    fn test_supertraits() {
+        let attr = MryAttr::from_list(
+            &NestedMeta::parse_meta_list(parse_quote! {
+                supertraits(Named, a::Base)
+            })
+            .unwrap(),
+        )
+        .unwrap();
+        let lists = attr.supertraits.unwrap().0;
+        assert_eq!(lists.len(), 2);
+        assert_eq!(lists[0], parse_quote!(Named));
+        assert_eq!(lists[1], parse_quote!(a::Base));
+    }
+
+    #[test]
+    fn test_fallback() {
+        let attr = MryAttr::from_list(
+            &NestedMeta::parse_meta_list(parse_quote! {
+                fallback = "default"
+            })
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            attr.fallback.unwrap().to_token_stream().to_string(),
+            quote![mry::Fallback::Default].to_string()
+        );
+    }
+
+    #[test]
+    fn test_unknown_fallback() {
+        assert!(MryAttr::from_list(
+            &NestedMeta::parse_meta_list(parse_quote! {
+                fallback = "nice"
+            })
+            .unwrap(),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_clone() {
+        let attr = MryAttr::from_list(
+            &NestedMeta::parse_meta_list(parse_quote! {
+                clone = "deep"
+            })
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            attr.custom_clone().unwrap().mry().to_string(),
+            quote![self.mry.fork()].to_string()
+        );
+    }
+
+    #[test]
+    fn test_clone_share() {
+        let attr = MryAttr::from_list(
+            &NestedMeta::parse_meta_list(parse_quote! {
+                clone = "share"
+            })
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            attr.custom_clone().unwrap().mry().to_string(),
+            quote![self.mry.share()].to_string()
+        );
+    }
+
+    #[test]
+    fn test_unknown_clone() {
+        assert!(MryAttr::from_list(
+            &NestedMeta::parse_meta_list(parse_quote! {
+                clone = "shallow"
+            })
+            .unwrap(),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_by_ref() {
+        let attr = MryAttr::from_list(
+            &NestedMeta::parse_meta_list(parse_quote! {
+                by_ref(File, TcpStream = |stream: &TcpStream| -> String { stream.to_string() }),
+                by_ref_fns(write)
+            })
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(matches!(
+            attr.by_ref_arg(&parse_quote!(open), &parse_quote!(&File)),
+            Some(None)
+        ));
+        assert!(attr
+            .by_ref_arg(&parse_quote!(open), &parse_quote!(TcpStream))
+            .unwrap()
+            .is_some());
+        assert!(attr
+            .by_ref_arg(&parse_quote!(open), &parse_quote!(String))
+            .is_none());
+        assert!(matches!(
+            attr.by_ref_arg(&parse_quote!(write), &parse_quote!(String)),
+            Some(None)
+        ));
+    }
+
+    #[test]
+    fn test_by_ref_projection_without_return_type() {
+        assert!(MryAttr::from_list(
+            &NestedMeta::parse_meta_list(parse_quote! {
+                by_ref(File = |file: &File| file.path())
+            })
+            .unwrap(),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_arg_into() {
+        let attr = MryAttr::from_list(
+            &NestedMeta::parse_meta_list(parse_quote! {
+                arg_into(body = Vec<u8>, path = std::path::PathBuf), debug
+            })
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            attr.arg_into(&parse_quote!(body)),
+            Some(&parse_quote!(Vec<u8>))
+        );
+        assert_eq!(
+            attr.arg_into(&parse_quote!(path)),
+            Some(&parse_quote!(std::path::PathBuf))
+        );
+        assert_eq!(attr.arg_into(&parse_quote!(count)), None);
+        assert!(attr.debug.is_present());
+    }
+
+    #[test]
+    fn test_owned() {
+        let attr = MryAttr::from_list(
+            &NestedMeta::parse_meta_list(parse_quote! {
+                owned(Request<'_> => OwnedRequest, Box<dyn Handler + 'static> => String)
+            })
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            attr.owned(&parse_quote!(Request<'a>)),
+            Some(&parse_quote!(OwnedRequest))
+        );
+        assert_eq!(
+            attr.owned(&parse_quote!(Request)),
+            Some(&parse_quote!(OwnedRequest))
+        );
+        assert_eq!(
+            attr.owned(&parse_quote!(Box<dyn Handler + 'a>)),
+            Some(&parse_quote!(String))
+        );
+        assert_eq!(attr.owned(&parse_quote!(Response<'a>)), None);
+    }
+
     #[test]
     fn test_skip_method() {
         let attr = MryAttr::from_list(
@@ -175,4 +644,32 @@ mod tests {
         assert!(attr.should_skip_method(&parse_quote!(skipped)));
         assert!(!attr.should_skip_method(&parse_quote!(not_skipped)));
     }
+
+    #[test]
+    fn test_handle() {
+        let attr = MryAttr::from_list(
+            &NestedMeta::parse_meta_list(parse_quote! {
+                handle
+            })
+            .unwrap(),
+        )
+        .unwrap();
+        let handle = attr.handle.unwrap();
+        assert_eq!(handle.0, "handle");
+        assert_eq!(handle.type_ident(&parse_quote!(Cat)), "CatHandle");
+
+        let attr = MryAttr::from_list(
+            &NestedMeta::parse_meta_list(parse_quote! {
+                handle = "store_controller"
+            })
+            .unwrap(),
+        )
+        .unwrap();
+        let handle = attr.handle.unwrap();
+        assert_eq!(handle.0, "store_controller");
+        assert_eq!(
+            handle.type_ident(&parse_quote!(MockStore)),
+            "MockStoreStoreController"
+        );
+    }
 }
@@ -41,6 +41,33 @@ pub fn create() -> TokenStream {
                 }
             }
 
+            impl<Fn, O, #(#types),*> From<Fn> for WithArgs<(#(#types,)*), O>
+            where
+                Fn: FnMut(#(#types),*) -> O + Send + 'static,
+            {
+                fn from(mut function: Fn) -> Self {
+                    WithArgs(Box::new(move |(#(#args,)*)| function(#(#args),*)))
+                }
+            }
+
+            impl<Fn, S, O, #(#types),*> From<Fn> for WithSelf<S, (#(#types,)*), O>
+            where
+                Fn: FnMut(&S, #(#types),*) -> O + Send + 'static,
+            {
+                fn from(mut function: Fn) -> Self {
+                    WithSelf(Box::new(move |this, (#(#args,)*)| function(this, #(#args),*)))
+                }
+            }
+
+            impl<Fn, S, O, #(#types),*> From<Fn> for WithSelfMut<S, (#(#types,)*), O>
+            where
+                Fn: FnMut(&mut S, #(#types),*) -> O + Send + 'static,
+            {
+                fn from(mut function: Fn) -> Self {
+                    WithSelfMut(Box::new(move |this, (#(#args,)*)| function(this, #(#args),*)))
+                }
+            }
+
             impl<I: Clone, O> Into<Behavior<I, O>> for #behavior_name<I, O> {
                 fn into(self) -> Behavior<I, O> {
                     Behavior::Function {
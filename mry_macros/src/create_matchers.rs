@@ -22,12 +22,25 @@ pub(crate) fn create() -> TokenStream {
             let index = Index::from(index);
             quote![self.#index.matches(#arg)]
         });
+        let len = nargs as usize;
+        let descriptions = (0..len).map(|index| {
+            let index = Index::from(index);
+            quote![self.#index.description()]
+        });
         let args = quote![#(#args,)*];
         quote! {
             impl<#(#trait_bounds),*> Match<(#(#types,)*)> for (#matchers) {
                 fn matches(&self, (#args): &(#(#types,)*)) -> bool {
                     #(#matches &&)* true
                 }
+
+                fn describe(&self) -> Option<String> {
+                    let descriptions: [Option<&str>; #len] = [#(#descriptions),*];
+                    descriptions.iter().any(Option::is_some).then(|| {
+                        let descriptions: Vec<_> = descriptions.iter().map(|description| description.unwrap_or("_")).collect();
+                        format!("({})", descriptions.join(", "))
+                    })
+                }
             }
 
             impl<#(#trait_bounds),*> From<(#matchers)> for Matcher<(#(#types,)*)> {
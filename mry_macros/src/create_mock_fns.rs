@@ -0,0 +1,46 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::Ident;
+
+use crate::MAX_ARGUMENT_COUNT;
+
+pub(crate) fn create() -> TokenStream {
+    let items = (0..=MAX_ARGUMENT_COUNT).map(|nargs| {
+        let (args, types): (Vec<_>, Vec<_>) = (1..=nargs)
+            .map(|i| {
+                let name = format!("Arg{i}");
+                (
+                    Ident::new(&name.to_lowercase(), Span::call_site()),
+                    Ident::new(&name, Span::call_site()),
+                )
+            })
+            .unzip();
+        let behavior_name = Ident::new(&format!("Behavior{nargs}"), Span::call_site());
+        let trait_bounds: Vec<_> = types.iter().map(|ty| quote![#ty: MockableArg]).collect();
+        quote! {
+            impl<O: MockableRet, #(#trait_bounds),*> MockFnInput<O> for (#(#types,)*) {
+                type Fn = Box<dyn Fn(#(#types),*) -> O + Send + Sync>;
+
+                fn mock_fn(mocks: Arc<Mutex<Mocks>>) -> Self::Fn {
+                    Box::new(move |#(#args),*| call(&mocks, (#(#args,)*)))
+                }
+            }
+
+            impl<O: MockableRet, #(#trait_bounds),*> MockFn<(#(#types,)*), O> {
+                /// Returns a mock locator for calls matching the arguments
+                #[must_use]
+                #[allow(clippy::too_many_arguments)]
+                pub fn mock(&self, #(#args: impl Into<crate::ArgMatcher<#types>>),*) -> crate::MockLocator<(#(#types,)*), O, O, crate::#behavior_name<(#(#types,)*), O>> {
+                    crate::MockLocator::new(
+                        self.mocks.clone(),
+                        key::<(#(#types,)*), O>(),
+                        NAME,
+                        (#(#args.into(),)*).into(),
+                        std::convert::identity,
+                    )
+                }
+            }
+        }
+    });
+    quote![#(#items)*]
+}
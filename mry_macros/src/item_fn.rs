@@ -11,9 +11,11 @@ pub(crate) fn transform(mry_attr: &MryAttr, input: ItemFn) -> TokenStream {
         Default::default(),
         "",
         quote![mry::static_record_call_and_find_mock_output],
+        false,
         Some(&input.vis),
         &input.attrs,
         &input.sig,
+        &Default::default(),
         &input
             .block
             .stmts
@@ -53,7 +55,7 @@ mod test {
                 #[cfg_attr(debug_assertions, track_caller)]
                 fn meow(count: usize) -> String {
                     #[cfg(debug_assertions)]
-                    if let Some(out) = mry::static_record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&meow), "meow", (<usize>::clone(&count),)) {
+                    if let Some(out) = mry::static_record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&meow), "meow", (<usize>::clone(&count),), mry::__fallback!(String)) {
                         return out;
                     }
                     (move || {
@@ -92,7 +94,7 @@ mod test {
                 #[cfg_attr(debug_assertions, track_caller)]
                 fn _meow(count: usize) -> String {
                     #[cfg(debug_assertions)]
-                    if let Some(out) = mry::static_record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&_meow), "_meow", (<usize>::clone(&count),)) {
+                    if let Some(out) = mry::static_record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&_meow), "_meow", (<usize>::clone(&count),), mry::__fallback!(String)) {
                         return out;
                     }
                     (move || {
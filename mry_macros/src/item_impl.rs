@@ -1,8 +1,10 @@
+use crate::attrs::Handle;
 use crate::{method, MryAttr};
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
+use syn::visit::Visit;
 use syn::visit_mut::VisitMut;
-use syn::{parse2, FnArg, Ident, ImplItem, ItemImpl, Path};
+use syn::{parse2, FnArg, Ident, ImplItem, ItemImpl, Path, Type};
 
 struct QualifiesAssociatedTypes(Path, Vec<Ident>);
 impl VisitMut for QualifiesAssociatedTypes {
@@ -34,6 +36,13 @@ impl VisitMut for QualifiesAssociatedTypes {
     }
 }
 
+struct HasLifetime(bool);
+impl<'ast> Visit<'ast> for HasLifetime {
+    fn visit_lifetime(&mut self, _: &'ast syn::Lifetime) {
+        self.0 = true;
+    }
+}
+
 pub(crate) fn transform(mry_attr: &MryAttr, mut input: ItemImpl) -> TokenStream {
     if let Some((_, path, _)) = input.trait_.clone() {
         let ty = path.clone();
@@ -92,6 +101,17 @@ pub(crate) fn transform(mry_attr: &MryAttr, mut input: ItemImpl) -> TokenStream
         .replace(" <", "<")
         .replace("< ", "<");
 
+    // Receivers with lifetimes cannot be lent to behaviors as `dyn Any`
+    let mut lifetimes = HasLifetime(false);
+    lifetimes.visit_type(struct_type);
+    let lend_receiver = !lifetimes.0;
+    let record_call = if lend_receiver {
+        quote![self.mry.record_call]
+    } else {
+        quote![self.mry.record_call_and_find_mock_output]
+    };
+
+    let mut handle_members = Vec::new();
     let (members, impl_members): (Vec<_>, Vec<_>) = input
         .items
         .iter()
@@ -101,15 +121,17 @@ pub(crate) fn transform(mry_attr: &MryAttr, mut input: ItemImpl) -> TokenStream
                     return (item.to_token_stream(), TokenStream::default());
                 }
                 if let Some(FnArg::Receiver(_)) = method.sig.inputs.first() {
-                    method::transform(
+                    let transformed = method::transform(
                         mry_attr,
                         quote![self.mry.mocks()],
                         quote![#qualified_type::],
                         &(type_name.clone() + "::"),
-                        quote![self.mry.record_call_and_find_mock_output],
+                        record_call.clone(),
+                        lend_receiver,
                         Some(&method.vis),
                         &method.attrs,
                         &method.sig,
+                        &input.generics,
                         &method.block.stmts.iter().fold(
                             TokenStream::default(),
                             |mut stream, item| {
@@ -118,7 +140,12 @@ pub(crate) fn transform(mry_attr: &MryAttr, mut input: ItemImpl) -> TokenStream
                             },
                         ),
                         false,
-                    )
+                    );
+                    handle_members.push(method::replace_self(
+                        transformed.1.clone(),
+                        &struct_type.to_token_stream(),
+                    ));
+                    transformed
                 } else {
                     method::transform(
                         mry_attr,
@@ -126,9 +153,11 @@ pub(crate) fn transform(mry_attr: &MryAttr, mut input: ItemImpl) -> TokenStream
                         quote![#qualified_type::],
                         &(type_name.clone() + "::"),
                         quote![mry::static_record_call_and_find_mock_output],
+                        false,
                         Some(&method.vis),
                         &method.attrs,
                         &method.sig,
+                        &input.generics,
                         &method.block.stmts.iter().fold(
                             TokenStream::default(),
                             |mut stream, item| {
@@ -147,6 +176,11 @@ pub(crate) fn transform(mry_attr: &MryAttr, mut input: ItemImpl) -> TokenStream
 
     let where_clause = &generics.where_clause;
 
+    let handle_impl = match &mry_attr.handle {
+        Some(handle) => impl_handle(&input, &handle.0, &handle_members),
+        None => TokenStream::default(),
+    };
+
     quote! {
         impl #generics #trait_ #struct_type #where_clause {
             #(#members)*
@@ -155,6 +189,75 @@ pub(crate) fn transform(mry_attr: &MryAttr, mut input: ItemImpl) -> TokenStream
         impl #generics #struct_type #where_clause {
             #(#impl_members)*
         }
+
+        #handle_impl
+    }
+}
+
+/// The accessor and the type of the handle opted in by `#[mry::mry(handle)]`, like
+/// `CatHandle<A>` of `Cat<A>` with the `mock_*` methods of the impl block
+fn impl_handle(input: &ItemImpl, accessor: &Ident, handle_members: &[TokenStream]) -> TokenStream {
+    let Type::Path(struct_path) = &*input.self_ty else {
+        return quote!(compile_error!("`handle` of mry needs a struct type"););
+    };
+    let Some(struct_ident) = struct_path
+        .path
+        .segments
+        .last()
+        .map(|segment| &segment.ident)
+    else {
+        return quote!(compile_error!("`handle` of mry needs a struct type"););
+    };
+    // The accessor would conflict with the method with the same name
+    if input
+        .items
+        .iter()
+        .any(|item| matches!(item, ImplItem::Fn(method) if &method.sig.ident == accessor))
+    {
+        let message = format!(
+            "`{accessor}` conflicts with the handle of mry, which can be named like `handle = \"controller\"`"
+        );
+        return quote!(compile_error!(#message););
+    }
+    let struct_type = &input.self_ty;
+    let handle_ident = Handle(accessor.clone()).type_ident(struct_ident);
+    let generics = &input.generics;
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        /// Sets the rules of a mock object and asserts its calls from any thread
+        #[cfg(debug_assertions)]
+        pub struct #handle_ident #generics #where_clause {
+            pub mry: mry::Mry,
+            _phantom: std::marker::PhantomData<fn() -> #struct_type>,
+        }
+
+        #[cfg(debug_assertions)]
+        impl #impl_generics Clone for #handle_ident #type_generics #where_clause {
+            fn clone(&self) -> Self {
+                Self {
+                    mry: self.mry.share(),
+                    _phantom: std::marker::PhantomData,
+                }
+            }
+        }
+
+        #[cfg(debug_assertions)]
+        impl #impl_generics #struct_type #where_clause {
+            /// Returns a handle to set the rules and assert the calls after the object is moved
+            /// into `Arc` or another thread.
+            pub fn #accessor(&self) -> #handle_ident #type_generics {
+                #handle_ident {
+                    mry: self.mry.share(),
+                    _phantom: std::marker::PhantomData,
+                }
+            }
+        }
+
+        #[cfg(debug_assertions)]
+        impl #impl_generics #handle_ident #type_generics #where_clause {
+            #(#handle_members)*
+        }
     }
 }
 
@@ -188,7 +291,7 @@ mod test {
                     #[cfg_attr(debug_assertions, track_caller)]
                     fn meow(#[a] &self, #[b] count: usize) -> String {
                         #[cfg(debug_assertions)]
-                        if let Some(out) = self.mry.record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&<Cat>::meow), "Cat::meow", (<usize>::clone(&count),)) {
+                        if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&<Cat>::meow), "Cat::meow", (<usize>::clone(&count),), mry::__fallback!(String)).output_with(self) {
                             return out;
                         }
                         (move || {
@@ -233,7 +336,7 @@ mod test {
                     #[cfg_attr(debug_assertions, track_caller)]
                     fn meow<'a, B>(&'a self, count: usize) -> B {
                         #[cfg(debug_assertions)]
-                        if let Some(out) = self.mry.record_call_and_find_mock_output::<_, B>(std::any::Any::type_id(&<Cat<'_, A> >::meow::<B>), "Cat<'a, A>::meow", (<usize>::clone(&count),)) {
+                        if let Some(out) = self.mry.record_call_and_find_mock_output::<_, B>(std::any::Any::type_id(&<Cat<'_, A> >::meow::<B>), "Cat<'a, A>::meow", (<usize>::clone(&count),), mry::__fallback!(B)) {
                             return out;
                         }
                         (move || {
@@ -278,7 +381,7 @@ mod test {
                     #[cfg_attr(debug_assertions, track_caller)]
                     fn name(&self) -> String {
                         #[cfg(debug_assertions)]
-                        if let Some(out) = self.mry.record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&<Cat as Animal<A> >::name), "<Cat as Animal<A>>::name", ()) {
+                        if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&<Cat as Animal<A> >::name), "<Cat as Animal<A>>::name", (), mry::__fallback!(String)).output_with(self) {
                             return out;
                         }
                         (move || {
@@ -325,7 +428,7 @@ mod test {
                     #[cfg_attr(debug_assertions, track_caller)]
                     fn next(&self) -> Option< <Self as Iterator>::Item> {
                         #[cfg(debug_assertions)]
-                        if let Some(out) = self.mry.record_call_and_find_mock_output::<_, Option< <Self as Iterator>::Item> >(std::any::Any::type_id(&<Cat as Iterator>::next), "<Cat as Iterator>::next", ()) {
+                        if let Some(out) = self.mry.record_call::<_, Option< <Self as Iterator>::Item> >(std::any::Any::type_id(&<Cat as Iterator>::next), "<Cat as Iterator>::next", (), mry::__fallback!(Option< <Self as Iterator>::Item> )).output_with(self) {
                             return out;
                         }
                         (move || {
@@ -370,7 +473,7 @@ mod test {
                     #[cfg_attr(debug_assertions, track_caller)]
                     fn meow(count: usize) -> String {
                         #[cfg(debug_assertions)]
-                        if let Some(out) = mry::static_record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&<Cat>::meow), "Cat::meow", (<usize>::clone(&count),)) {
+                        if let Some(out) = mry::static_record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&<Cat>::meow), "Cat::meow", (<usize>::clone(&count),), mry::__fallback!(String)) {
                             return out;
                         }
                         (move || {
@@ -485,7 +588,7 @@ mod test {
                     #[cfg_attr(debug_assertions, track_caller)]
                     fn meow(&self, value: T) -> String {
                         #[cfg(debug_assertions)]
-                        if let Some(out) = self.mry.record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&<Cat<T> >::meow), "Cat<T>::meow", (<T>::clone(&value),)) {
+                        if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&<Cat<T> >::meow), "Cat<T>::meow", (<T>::clone(&value),), mry::__fallback!(String)).output_with(self) {
                             return out;
                         }
                         (move || {
@@ -539,7 +642,7 @@ mod test {
                     #[cfg_attr(debug_assertions, track_caller)]
                     fn name(&self, prefix: T) -> String {
                         #[cfg(debug_assertions)]
-                        if let Some(out) = self.mry.record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&<Cat<T> as Animal<T>  >::name), "<Cat<T> as Animal<T>>::name", (<T>::clone(&prefix),)) {
+                        if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&<Cat<T> as Animal<T>  >::name), "<Cat<T> as Animal<T>>::name", (<T>::clone(&prefix),), mry::__fallback!(String)).output_with(self) {
                             return out;
                         }
                         (move || {
@@ -568,4 +671,81 @@ mod test {
             .to_string()
         );
     }
+    #[test]
+    fn handle_is_opted_in() {
+        let attr = MryAttr::from_meta(&parse_quote! {
+            mry(handle)
+        })
+        .unwrap();
+        let input: ItemImpl = parse2(quote! {
+            impl<A: Clone> Cat<A> {
+                fn meow(&self) -> A {
+                    self.name.clone()
+                }
+            }
+        })
+        .unwrap();
+
+        let output = transform(&attr, input).to_string();
+        assert!(output.contains(
+            &quote! {
+                /// Sets the rules of a mock object and asserts its calls from any thread
+                #[cfg(debug_assertions)]
+                pub struct CatHandle<A: Clone> {
+                    pub mry: mry::Mry,
+                    _phantom: std::marker::PhantomData<fn() -> Cat<A> >,
+                }
+            }
+            .to_string()
+        ));
+        assert!(output.contains(
+            &quote! {
+                pub fn handle(&self) -> CatHandle<A> {
+                    CatHandle {
+                        mry: self.mry.share(),
+                        _phantom: std::marker::PhantomData,
+                    }
+                }
+            }
+            .to_string()
+        ));
+        assert!(output.contains(
+            &quote! {
+                #[cfg(debug_assertions)]
+                #[must_use]
+                pub fn mock_meow(&mut self,) -> mry::MockLocator<(), A, A, mry::Behavior0<(), A> > {
+                    mry::MockLocator::new(
+                        self.mry.mocks(),
+                        std::any::Any::type_id(&<Cat<A> >::meow),
+                        "Cat<A>::meow",
+                        ().into(),
+                        std::convert::identity,
+                    )
+                }
+            }
+            .to_string()
+        ));
+        assert!(output.contains(&quote![impl<A: Clone> CatHandle<A>].to_string()));
+        assert!(!transform(&MryAttr::default(), parse_quote!(impl Cat {}))
+            .to_string()
+            .contains("CatHandle"));
+    }
+
+    #[test]
+    fn handle_conflicting_with_method() {
+        let attr = MryAttr::from_meta(&parse_quote! {
+            mry(handle)
+        })
+        .unwrap();
+        let input: ItemImpl = parse2(quote! {
+            impl Cat {
+                fn handle(&self) {}
+            }
+        })
+        .unwrap();
+
+        assert!(transform(&attr, input)
+            .to_string()
+            .contains("compile_error"));
+    }
 }
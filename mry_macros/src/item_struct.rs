@@ -2,9 +2,12 @@ use proc_macro2::TokenStream;
 use proc_macro2::TokenTree;
 use quote::quote;
 use quote::ToTokens;
-use syn::ItemStruct;
+use syn::punctuated::Punctuated;
+use syn::{parse_quote, Attribute, ItemStruct, Path, Token};
 
-pub(crate) fn transform(input: ItemStruct) -> TokenStream {
+use crate::attrs::{ClonePolicy, MryAttr};
+
+pub(crate) fn transform(mry_attr: &MryAttr, input: ItemStruct) -> TokenStream {
     let vis = &input.vis;
     let struct_name = &input.ident;
 
@@ -31,7 +34,16 @@ pub(crate) fn transform(input: ItemStruct) -> TokenStream {
         TokenStream::default()
     };
 
-    let attrs = &input.attrs;
+    let (attrs, impl_clone) = match mry_attr.custom_clone() {
+        Some(policy) => match without_derived_clone(&input.attrs) {
+            Some(attrs) => (attrs, impl_clone(&input, policy)),
+            None => (
+                input.attrs.clone(),
+                quote!(compile_error!("`clone = ..` of mry replaces `#[derive(Clone)]`, which is not found");),
+            ),
+        },
+        None => (input.attrs.clone(), TokenStream::default()),
+    };
     let struct_fields = input
         .fields
         .iter()
@@ -65,11 +77,63 @@ pub(crate) fn transform(input: ItemStruct) -> TokenStream {
             #serde_skip_or_blank
             pub mry: mry::Mry,
         }
+        #impl_clone
+    }
+}
+
+/// Removes `Clone` from the derives, or returns `None` if it is not derived
+fn without_derived_clone(attrs: &[Attribute]) -> Option<Vec<Attribute>> {
+    let mut found = false;
+    let attrs = attrs
+        .iter()
+        .filter_map(|attr| {
+            if !attr.path().is_ident("derive") {
+                return Some(attr.clone());
+            }
+            let paths = attr
+                .parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)
+                .ok()?;
+            let (clone, rest): (Vec<_>, Vec<_>) = paths.into_iter().partition(|path| {
+                path.segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "Clone")
+            });
+            found |= !clone.is_empty();
+            (!rest.is_empty()).then(|| parse_quote!(#[derive(#(#rest),*)]))
+        })
+        .collect();
+    found.then_some(attrs)
+}
+
+/// Implements `Clone` like the derived one, with the `mry` field cloned by the policy
+fn impl_clone(input: &ItemStruct, policy: ClonePolicy) -> TokenStream {
+    let struct_name = &input.ident;
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(Clone));
+    }
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    let fields = input.fields.iter().map(|field| {
+        let name = &field.ident;
+        quote![#name: Clone::clone(&self.#name),]
+    });
+    let mry = policy.mry();
+
+    quote! {
+        impl #impl_generics Clone for #struct_name #type_generics #where_clause {
+            fn clone(&self) -> Self {
+                Self {
+                    #(#fields)*
+                    mry: #mry,
+                }
+            }
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
+    use darling::FromMeta as _;
     use pretty_assertions::assert_eq;
     use syn::parse2;
 
@@ -85,7 +149,7 @@ mod test {
         .unwrap();
 
         assert_eq!(
-            transform(input).to_string(),
+            transform(&MryAttr::default(), input).to_string(),
             quote! {
                 struct Cat {
                     name: String,
@@ -108,7 +172,7 @@ mod test {
         .unwrap();
 
         assert_eq!(
-            transform(input).to_string(),
+            transform(&MryAttr::default(), input).to_string(),
             quote! {
                 #[derive(Clone, Default)]
                 struct Cat {
@@ -131,7 +195,7 @@ mod test {
         .unwrap();
 
         assert_eq!(
-            transform(input).to_string(),
+            transform(&MryAttr::default(), input).to_string(),
             quote! {
                 pub struct Cat {
                     pub name: String,
@@ -152,12 +216,13 @@ mod test {
         .unwrap();
 
         assert_eq!(
-            transform(input).to_string(),
+            transform(&MryAttr::default(), input).to_string(),
             quote! {
                 pub struct Cat<'a, A> {
                     pub name: &'a A,
                     pub mry : mry::Mry,
                 }
+
             }
             .to_string()
         );
@@ -172,7 +237,7 @@ mod test {
         .unwrap();
 
         assert_eq!(
-            transform(input).to_string(),
+            transform(&MryAttr::default(), input).to_string(),
             quote! {
                 struct Cat {
                     pub mry : mry::Mry,
@@ -193,7 +258,7 @@ mod test {
         .unwrap();
 
         assert_eq!(
-            transform(input).to_string(),
+            transform(&MryAttr::default(), input).to_string(),
             quote! {
                 #[derive(Debug, Clone, PartialEq, Serialize)]
                 struct Cat {
@@ -217,7 +282,7 @@ mod test {
         .unwrap();
 
         assert_eq!(
-            transform(input).to_string(),
+            transform(&MryAttr::default(), input).to_string(),
             quote! {
                 #[derive(Debug, Clone, PartialEq, serde::Deserialize)]
                 struct Cat {
@@ -229,4 +294,58 @@ mod test {
             .to_string()
         );
     }
+
+    #[test]
+    fn clone_policy_replaces_derived_clone() {
+        let attr = MryAttr::from_meta(&parse_quote! {
+            mry(clone = "deep")
+        })
+        .unwrap();
+        let input: ItemStruct = parse2(quote! {
+            #[derive(Clone, Default)]
+            struct Cat<A> {
+                name: A,
+            }
+        })
+        .unwrap();
+
+        let output = transform(&attr, input).to_string();
+        assert!(output.starts_with(
+            &quote! {
+                #[derive(Default)]
+                struct Cat<A> {
+                    name: A,
+                    pub mry : mry::Mry,
+                }
+
+                impl<A: Clone> Clone for Cat<A> {
+                    fn clone(&self) -> Self {
+                        Self {
+                            name: Clone::clone(&self.name),
+                            mry: self.mry.fork(),
+                        }
+                    }
+                }
+            }
+            .to_string()
+        ));
+    }
+
+    #[test]
+    fn clone_policy_requires_derived_clone() {
+        let attr = MryAttr::from_meta(&parse_quote! {
+            mry(clone = "fresh")
+        })
+        .unwrap();
+        let input: ItemStruct = parse2(quote! {
+            struct Cat {
+                name: String,
+            }
+        })
+        .unwrap();
+
+        assert!(transform(&attr, input)
+            .to_string()
+            .contains("compile_error"));
+    }
 }
@@ -1,10 +1,252 @@
 use proc_macro2::{Span, TokenStream};
-use quote::{quote, ToTokens};
-use syn::{parse_quote, Ident, ItemTrait};
+use quote::{format_ident, quote, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::{braced, bracketed, parse_quote, Ident, ItemTrait, Path, Token};
 
-use crate::{attrs::MryAttr, method};
+use crate::attrs::{ClonePolicy, MryAttr};
+use crate::method;
 
-pub(crate) fn transform(mry_attr: &MryAttr, mut input: ItemTrait) -> TokenStream {
+pub(crate) fn transform(mry_attr: &MryAttr, input: ItemTrait) -> TokenStream {
+    let trait_ident = &input.ident;
+    let mry_ident = Ident::new(&format!("Mock{}", &input.ident), Span::call_site());
+    let vis = &input.vis;
+    let supertrait_macro = supertrait_macro(mry_attr, &input);
+    // A mock of a trait has no real implementation to fall back to, so the fallback applies
+    // from the construction rather than after the first `mock_*` call.
+    let (derive_default, impl_default) = match &mry_attr.fallback {
+        Some(policy) => (
+            TokenStream::default(),
+            quote! {
+                #[cfg(debug_assertions)]
+                impl Default for #mry_ident {
+                    fn default() -> Self {
+                        Self {
+                            mry: mry::Mry::with_fallback(#policy),
+                        }
+                    }
+                }
+            },
+        ),
+        None => (quote![Default,], TokenStream::default()),
+    };
+    let (derive_clone, impl_clone) = match mry_attr.custom_clone() {
+        Some(policy) => {
+            // A fresh clone is made like a new mock, with the fallback given to the trait
+            let clone = match policy {
+                ClonePolicy::Fresh => quote![Self::default()],
+                _ => {
+                    let mry = policy.mry();
+                    quote![Self { mry: #mry }]
+                }
+            };
+            (
+                TokenStream::default(),
+                quote! {
+                    #[cfg(debug_assertions)]
+                    impl Clone for #mry_ident {
+                        fn clone(&self) -> Self {
+                            #clone
+                        }
+                    }
+                },
+            )
+        }
+        None => (quote![Clone,], TokenStream::default()),
+    };
+    let handle_ident = mry_attr
+        .handle
+        .as_ref()
+        .map(|handle| handle.type_ident(&mry_ident));
+    let handle = mry_attr
+        .handle
+        .as_ref()
+        .zip(handle_ident.as_ref())
+        .map(|(handle, handle_ident)| trait_handle(&input, &mry_ident, &handle.0, handle_ident));
+    let impls = mock_impls(
+        mry_attr,
+        &input,
+        &mry_ident,
+        handle_ident.as_ref(),
+        &quote![#trait_ident],
+    );
+    let supertraits = mry_attr.supertraits.iter().flat_map(|supertraits| {
+        supertraits.0.iter().map(|path| {
+            let mut macro_path = path.clone();
+            let last = macro_path.segments.last_mut().unwrap();
+            last.ident = supertrait_macro_ident(&last.ident);
+            quote! {
+                #[cfg(debug_assertions)]
+                #macro_path!(#mry_ident [#handle_ident], #path);
+            }
+        })
+    });
+
+    quote! {
+        #input
+
+        // This cfg(debug_assertions) is needed because `panic!` with return position impl
+        // trait is not supported yet in rustc. It is problem with using
+        // `trait_variant::make` macro that desugars `async fn`.
+        // See https://github.com/rust-lang/rust/issues/35121
+        #[cfg(debug_assertions)]
+        #[derive(#derive_default #derive_clone Debug)]
+        #vis struct #mry_ident {
+            pub mry: mry::Mry,
+        }
+        #impl_default
+        #impl_clone
+
+        #[cfg(debug_assertions)]
+        impl #mry_ident {
+            /// Creates a mock that returns `Default::default()` from methods with no matching rule.
+            pub fn nice() -> Self {
+                Self {
+                    mry: mry::Mry::nice(),
+                }
+            }
+
+            /// Creates a mock with a name that appears in panic and assertion messages.
+            pub fn named(name: impl Into<String>) -> Self {
+                let mut mock = Self::default();
+                mock.mry.set_name(name);
+                mock
+            }
+        }
+        #handle
+        #impls
+        #(#supertraits)*
+        #supertrait_macro
+    }
+}
+
+/// Input of `mry::__mock_supertrait!`: `{ attr } { trait } MockType [HandleType], path::to::Trait`
+pub(crate) struct Supertrait {
+    pub attr: TokenStream,
+    pub item: ItemTrait,
+    pub mry_ident: Ident,
+    pub handle_ident: Option<Ident>,
+    pub path: Path,
+}
+
+impl Parse for Supertrait {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attr;
+        braced!(attr in input);
+        let item;
+        braced!(item in input);
+        let mry_ident = input.parse()?;
+        let handle_ident;
+        bracketed!(handle_ident in input);
+        input.parse::<Token![,]>()?;
+        Ok(Self {
+            attr: attr.parse()?,
+            item: item.parse()?,
+            mry_ident,
+            handle_ident: handle_ident.parse()?,
+            path: input.parse()?,
+        })
+    }
+}
+
+/// Implements a trait annotated with `#[mry::mry]` for the mock of its subtrait, and adds its
+/// `mock_*` methods to the handle of the subtrait mock.
+pub(crate) fn transform_supertrait(mry_attr: &MryAttr, input: Supertrait) -> TokenStream {
+    let path = &input.path;
+    mock_impls(
+        mry_attr,
+        &input.item,
+        &input.mry_ident,
+        input.handle_ident.as_ref(),
+        &quote![#path],
+    )
+}
+
+/// The accessor and the type of the handle opted in by `#[mry::mry(handle)]`
+fn trait_handle(
+    input: &ItemTrait,
+    mry_ident: &Ident,
+    accessor: &Ident,
+    handle_ident: &Ident,
+) -> TokenStream {
+    let vis = &input.vis;
+    // The accessor would shadow the method of the trait with the same name
+    let accessor = if input
+        .items
+        .iter()
+        .any(|item| matches!(item, syn::TraitItem::Fn(method) if &method.sig.ident == accessor))
+    {
+        let message = format!(
+            "`{accessor}` of the trait is shadowed by the handle of mry, which can be named like `handle = \"controller\"`"
+        );
+        quote!(compile_error!(#message);)
+    } else {
+        quote! {
+            #[cfg(debug_assertions)]
+            impl #mry_ident {
+                /// Returns a handle to set the rules and assert the calls after the mock is moved
+                /// into `Arc<dyn Trait>` or another thread.
+                pub fn #accessor(&self) -> #handle_ident {
+                    #handle_ident {
+                        mry: self.mry.share(),
+                    }
+                }
+            }
+        }
+    };
+
+    quote! {
+        #accessor
+
+        /// Sets the rules of a mock object and asserts its calls from any thread
+        #[cfg(debug_assertions)]
+        #[derive(Debug)]
+        #vis struct #handle_ident {
+            pub mry: mry::Mry,
+        }
+
+        #[cfg(debug_assertions)]
+        impl Clone for #handle_ident {
+            fn clone(&self) -> Self {
+                Self {
+                    mry: self.mry.share(),
+                }
+            }
+        }
+    }
+}
+
+fn supertrait_macro_ident(trait_ident: &Ident) -> Ident {
+    format_ident!("__mry_supertrait_{}", trait_ident)
+}
+
+// Carries the trait definition to `#[mry::mry(supertraits(...))]` on its subtraits. The macro
+// is `pub(crate)`, so a supertrait in another crate cannot be listed.
+fn supertrait_macro(mry_attr: &MryAttr, input: &ItemTrait) -> TokenStream {
+    let macro_ident = supertrait_macro_ident(&input.ident);
+    let attr = &mry_attr.tokens;
+    quote! {
+        #[cfg(debug_assertions)]
+        #[doc(hidden)]
+        #[allow(unused_macros)]
+        macro_rules! #macro_ident {
+            ($mock:ident [$($handle:ident)?], $($trait_:tt)*) => {
+                mry::__mock_supertrait!({ #attr } { #input } $mock [$($handle)?], $($trait_)*);
+            };
+        }
+        #[cfg(debug_assertions)]
+        #[doc(hidden)]
+        #[allow(unused_imports)]
+        pub(crate) use #macro_ident;
+    }
+}
+
+fn mock_impls(
+    mry_attr: &MryAttr,
+    input: &ItemTrait,
+    mry_ident: &Ident,
+    handle_ident: Option<&Ident>,
+    trait_path: &TokenStream,
+) -> TokenStream {
     let async_trait_or_blank = if input.attrs.iter().any(|attr| {
         attr.path()
             .segments
@@ -18,13 +260,11 @@ pub(crate) fn transform(mry_attr: &MryAttr, mut input: ItemTrait) -> TokenStream
 
     let generics = &input.generics;
     let trait_ident = &input.ident;
-    let mry_ident = Ident::new(&format!("Mock{}", &input.ident), Span::call_site());
-    let vis = &input.vis;
     let panic_message = format!("mock not found for {trait_ident}");
-    let (def, generated): (Vec<_>, Vec<_>) = input
+    let generated: Vec<_> = input
         .items
         .iter()
-        .map(|item| match item {
+        .filter_map(|item| match item {
             syn::TraitItem::Fn(method) => {
                 if mry_attr.should_skip_method(&method.sig.ident) {
                     let mut method = method.clone();
@@ -32,15 +272,12 @@ pub(crate) fn transform(mry_attr: &MryAttr, mut input: ItemTrait) -> TokenStream
                         panic!("this method is skipped with `#[mry::mry(skip_fns(...))]` attribute")
                     }));
                     method.attrs.push(parse_quote!(#[allow(unused_variables)]));
-                    return (
-                        item.clone(),
-                        (
-                            syn::TraitItem::Fn(method).to_token_stream(),
-                            Default::default(),
-                        ),
-                    );
-                }
-                let method_prefix = quote![<#mry_ident as #trait_ident>::];
+                    return Some((
+                        syn::TraitItem::Fn(method).to_token_stream(),
+                        Default::default(),
+                    ));
+                }
+                let method_prefix = quote![<#mry_ident as #trait_path>::];
                 let body = &method
                     .default
                     .as_ref()
@@ -55,61 +292,66 @@ pub(crate) fn transform(mry_attr: &MryAttr, mut input: ItemTrait) -> TokenStream
                     })
                     .unwrap_or(quote![panic!(#panic_message)]);
                 if method.sig.receiver().is_none() {
-                    (
-                        item.clone(),
-                        method::transform(
-                            mry_attr,
-                            quote![mry::get_static_mocks()],
-                            method_prefix,
-                            &format!("<{mry_ident} as {trait_ident}>::"),
-                            quote![mry::static_record_call_and_find_mock_output],
-                            None,
-                            &method.attrs,
-                            &method.sig,
-                            body,
-                            method.default.is_none(),
-                        ),
-                    )
+                    Some(method::transform(
+                        mry_attr,
+                        quote![mry::get_static_mocks()],
+                        method_prefix,
+                        &format!("<{mry_ident} as {trait_ident}>::"),
+                        quote![mry::static_record_call_and_find_mock_output],
+                        false,
+                        None,
+                        &method.attrs,
+                        &method.sig,
+                        &input.generics,
+                        body,
+                        method.default.is_none(),
+                    ))
                 } else {
-                    (
-                        item.clone(),
-                        method::transform(
-                            mry_attr,
-                            quote![self.mry.mocks()],
-                            method_prefix,
-                            &(trait_ident.to_string() + "::"),
-                            quote![self.mry.record_call_and_find_mock_output],
-                            None,
-                            &method.attrs,
-                            &method.sig,
-                            body,
-                            method.default.is_none(),
-                        ),
-                    )
+                    Some(method::transform(
+                        mry_attr,
+                        quote![self.mry.mocks()],
+                        method_prefix,
+                        &(trait_ident.to_string() + "::"),
+                        quote![self.mry.record_call],
+                        true,
+                        None,
+                        &method.attrs,
+                        &method.sig,
+                        &input.generics,
+                        body,
+                        method.default.is_none(),
+                    ))
                 }
             }
-            item => (item.clone(), Default::default()),
+            _ => None,
         })
-        .unzip();
-    input.items = def;
+        .collect();
     let items = generated.iter().map(|item| &item.0);
     let impl_items = generated.iter().map(|item| &item.1);
+    let handle_impl = handle_ident.map(|handle_ident| {
+        // `generated` has an item for each method
+        let handle_items = input
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                syn::TraitItem::Fn(method) => Some(method.sig.receiver().is_some()),
+                _ => None,
+            })
+            .zip(&generated)
+            .filter(|(has_receiver, _)| *has_receiver)
+            .map(|(_, item)| method::replace_self(item.1.clone(), &mry_ident.to_token_stream()));
+        quote! {
+            #[cfg(debug_assertions)]
+            impl #handle_ident {
+                #(#handle_items)*
+            }
+        }
+    });
 
     quote! {
-        #input
-
-        // This cfg(debug_assertions) is needed because `panic!` with return position impl
-        // trait is not supported yet in rustc. It is problem with using
-        // `trait_variant::make` macro that desugars `async fn`.
-        // See https://github.com/rust-lang/rust/issues/35121
-        #[cfg(debug_assertions)]
-        #[derive(Default, Clone, Debug)]
-        #vis struct #mry_ident {
-            pub mry: mry::Mry,
-        }
         #[cfg(debug_assertions)]
         #async_trait_or_blank
-        impl #generics #trait_ident for #mry_ident {
+        impl #generics #trait_path for #mry_ident {
             #(#items)*
         }
 
@@ -117,6 +359,8 @@ pub(crate) fn transform(mry_attr: &MryAttr, mut input: ItemTrait) -> TokenStream
         impl #mry_ident {
             #(#impl_items)*
         }
+
+        #handle_impl
     }
 }
 
@@ -150,12 +394,28 @@ mod test {
                     pub mry : mry::Mry,
                 }
 
+                #[cfg(debug_assertions)]
+                impl MockCat {
+                    /// Creates a mock that returns `Default::default()` from methods with no matching rule.
+                    pub fn nice() -> Self {
+                        Self {
+                            mry: mry::Mry::nice(),
+                        }
+                    }
+                    /// Creates a mock with a name that appears in panic and assertion messages.
+                    pub fn named(name: impl Into<String>) -> Self {
+                        let mut mock = Self::default();
+                        mock.mry.set_name(name);
+                        mock
+                    }
+                }
+
                 #[cfg(debug_assertions)]
                 impl Cat for MockCat {
                     #[cfg_attr(debug_assertions, track_caller)]
                     fn meow(&self, count: usize) -> String {
                         #[cfg(debug_assertions)]
-                        if let Some(out) = self.mry.record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&<MockCat as Cat>::meow), "Cat::meow", (<usize>::clone(&count),)) {
+                        if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&<MockCat as Cat>::meow), "Cat::meow", (<usize>::clone(&count),), mry::__fallback!(String)).output_with(self) {
                             return out;
                         }
                         panic!("mock not found for Cat")
@@ -176,6 +436,23 @@ mod test {
                         )
                     }
                 }
+
+                #[cfg(debug_assertions)]
+                #[doc(hidden)]
+                #[allow(unused_macros)]
+                macro_rules! __mry_supertrait_Cat {
+                    ($mock:ident [$($handle:ident)?], $($trait_:tt)*) => {
+                        mry::__mock_supertrait!({ } {
+                            trait Cat {
+                                fn meow(&self, count: usize) -> String;
+                            }
+                        } $mock [$($handle)?], $($trait_)*);
+                    };
+                }
+                #[cfg(debug_assertions)]
+                #[doc(hidden)]
+                #[allow(unused_imports)]
+                pub(crate) use __mry_supertrait_Cat;
             }
             .to_string()
         );
@@ -203,12 +480,28 @@ mod test {
                     pub mry : mry::Mry,
                 }
 
+                #[cfg(debug_assertions)]
+                impl MockCat {
+                    /// Creates a mock that returns `Default::default()` from methods with no matching rule.
+                    pub fn nice() -> Self {
+                        Self {
+                            mry: mry::Mry::nice(),
+                        }
+                    }
+                    /// Creates a mock with a name that appears in panic and assertion messages.
+                    pub fn named(name: impl Into<String>) -> Self {
+                        let mut mock = Self::default();
+                        mock.mry.set_name(name);
+                        mock
+                    }
+                }
+
                 #[cfg(debug_assertions)]
                 impl Cat for MockCat {
                     #[cfg_attr(debug_assertions, track_caller)]
                     fn meow(&self, count: usize) -> String {
                         #[cfg(debug_assertions)]
-                        if let Some(out) = self.mry.record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&<MockCat as Cat>::meow), "Cat::meow", (<usize>::clone(&count),)) {
+                        if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&<MockCat as Cat>::meow), "Cat::meow", (<usize>::clone(&count),), mry::__fallback!(String)).output_with(self) {
                             return out;
                         }
                         panic!("mock not found for Cat")
@@ -229,6 +522,23 @@ mod test {
                         )
                     }
                 }
+
+                #[cfg(debug_assertions)]
+                #[doc(hidden)]
+                #[allow(unused_macros)]
+                macro_rules! __mry_supertrait_Cat {
+                    ($mock:ident [$($handle:ident)?], $($trait_:tt)*) => {
+                        mry::__mock_supertrait!({ } {
+                            pub trait Cat {
+                                fn meow(&self, count: usize) -> String;
+                            }
+                        } $mock [$($handle)?], $($trait_)*);
+                    };
+                }
+                #[cfg(debug_assertions)]
+                #[doc(hidden)]
+                #[allow(unused_imports)]
+                pub(crate) use __mry_supertrait_Cat;
             }
             .to_string()
         );
@@ -258,13 +568,30 @@ mod test {
                     pub mry : mry::Mry,
                 }
 
+                #[cfg(debug_assertions)]
+                impl MockCat {
+                    /// Creates a mock that returns `Default::default()` from methods with no matching rule.
+                    pub fn nice() -> Self {
+                        Self {
+                            mry: mry::Mry::nice(),
+                        }
+                    }
+
+                    /// Creates a mock with a name that appears in panic and assertion messages.
+                    pub fn named(name: impl Into<String>) -> Self {
+                        let mut mock = Self::default();
+                        mock.mry.set_name(name);
+                        mock
+                    }
+                }
+
                 #[cfg(debug_assertions)]
                 #[async_trait::async_trait]
                 impl Cat for MockCat {
                     #[cfg_attr(debug_assertions, track_caller)]
                     async fn meow(&self, count: usize) -> String {
                         #[cfg(debug_assertions)]
-                        if let Some(out) = self.mry.record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&<MockCat as Cat>::meow), "Cat::meow", (<usize>::clone(&count),)) {
+                        if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&<MockCat as Cat>::meow), "Cat::meow", (<usize>::clone(&count),), mry::__fallback!(String)).output_with(self) {
                             return out;
                         }
                         panic!("mock not found for Cat")
@@ -285,6 +612,24 @@ mod test {
                         )
                     }
                 }
+
+                #[cfg(debug_assertions)]
+                #[doc(hidden)]
+                #[allow(unused_macros)]
+                macro_rules! __mry_supertrait_Cat {
+                    ($mock:ident [$($handle:ident)?], $($trait_:tt)*) => {
+                        mry::__mock_supertrait!({ } {
+                            #[async_trait::async_trait]
+                            trait Cat {
+                                async fn meow(&self, count: usize) -> String;
+                            }
+                        } $mock [$($handle)?], $($trait_)*);
+                    };
+                }
+                #[cfg(debug_assertions)]
+                #[doc(hidden)]
+                #[allow(unused_imports)]
+                pub(crate) use __mry_supertrait_Cat;
             }
             .to_string()
         );
@@ -312,12 +657,28 @@ mod test {
                     pub mry : mry::Mry,
                 }
 
+                #[cfg(debug_assertions)]
+                impl MockCat {
+                    /// Creates a mock that returns `Default::default()` from methods with no matching rule.
+                    pub fn nice() -> Self {
+                        Self {
+                            mry: mry::Mry::nice(),
+                        }
+                    }
+                    /// Creates a mock with a name that appears in panic and assertion messages.
+                    pub fn named(name: impl Into<String>) -> Self {
+                        let mut mock = Self::default();
+                        mock.mry.set_name(name);
+                        mock
+                    }
+                }
+
                 #[cfg(debug_assertions)]
                 impl Cat for MockCat {
                     #[cfg_attr(debug_assertions, track_caller)]
                     fn _meow(&self, count: usize) -> String {
                         #[cfg(debug_assertions)]
-                        if let Some(out) = self.mry.record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&<MockCat as Cat>::_meow), "Cat::_meow", (<usize>::clone(&count),)) {
+                        if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&<MockCat as Cat>::_meow), "Cat::_meow", (<usize>::clone(&count),), mry::__fallback!(String)).output_with(self) {
                             return out;
                         }
                         panic!("mock not found for Cat")
@@ -339,6 +700,23 @@ mod test {
                         )
                     }
                 }
+
+                #[cfg(debug_assertions)]
+                #[doc(hidden)]
+                #[allow(unused_macros)]
+                macro_rules! __mry_supertrait_Cat {
+                    ($mock:ident [$($handle:ident)?], $($trait_:tt)*) => {
+                        mry::__mock_supertrait!({ } {
+                            trait Cat {
+                                fn _meow(&self, count: usize) -> String;
+                            }
+                        } $mock [$($handle)?], $($trait_)*);
+                    };
+                }
+                #[cfg(debug_assertions)]
+                #[doc(hidden)]
+                #[allow(unused_imports)]
+                pub(crate) use __mry_supertrait_Cat;
             }
             .to_string()
         );
@@ -366,12 +744,28 @@ mod test {
                     pub mry : mry::Mry,
                 }
 
+                #[cfg(debug_assertions)]
+                impl MockCat {
+                    /// Creates a mock that returns `Default::default()` from methods with no matching rule.
+                    pub fn nice() -> Self {
+                        Self {
+                            mry: mry::Mry::nice(),
+                        }
+                    }
+                    /// Creates a mock with a name that appears in panic and assertion messages.
+                    pub fn named(name: impl Into<String>) -> Self {
+                        let mut mock = Self::default();
+                        mock.mry.set_name(name);
+                        mock
+                    }
+                }
+
                 #[cfg(debug_assertions)]
                 impl Cat for MockCat {
                     #[cfg_attr(debug_assertions, track_caller)]
                     async fn meow(&self, count: usize) -> String {
                         #[cfg(debug_assertions)]
-                        if let Some(out) = self.mry.record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&<MockCat as Cat>::meow), "Cat::meow", (<usize>::clone(&count),)) {
+                        if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&<MockCat as Cat>::meow), "Cat::meow", (<usize>::clone(&count),), mry::__fallback!(String)).output_with(self) {
                             return out;
                         }
                         panic!("mock not found for Cat")
@@ -392,6 +786,23 @@ mod test {
                         )
                     }
                 }
+
+                #[cfg(debug_assertions)]
+                #[doc(hidden)]
+                #[allow(unused_macros)]
+                macro_rules! __mry_supertrait_Cat {
+                    ($mock:ident [$($handle:ident)?], $($trait_:tt)*) => {
+                        mry::__mock_supertrait!({ } {
+                            trait Cat {
+                                async fn meow(&self, count: usize) -> String;
+                            }
+                        } $mock [$($handle)?], $($trait_)*);
+                    };
+                }
+                #[cfg(debug_assertions)]
+                #[doc(hidden)]
+                #[allow(unused_imports)]
+                pub(crate) use __mry_supertrait_Cat;
             }
             .to_string()
         );
@@ -416,12 +827,28 @@ mod test {
                 pub mry : mry::Mry,
             }
 
+            #[cfg(debug_assertions)]
+            impl MockCat {
+                /// Creates a mock that returns `Default::default()` from methods with no matching rule.
+                pub fn nice() -> Self {
+                    Self {
+                        mry: mry::Mry::nice(),
+                    }
+                }
+                /// Creates a mock with a name that appears in panic and assertion messages.
+                pub fn named(name: impl Into<String>) -> Self {
+                    let mut mock = Self::default();
+                    mock.mry.set_name(name);
+                    mock
+                }
+            }
+
             #[cfg(debug_assertions)]
             impl Cat for MockCat {
                 #[cfg_attr(debug_assertions, track_caller)]
                 fn new(name: String) -> Self {
                     #[cfg(debug_assertions)]
-                    if let Some(out) = mry::static_record_call_and_find_mock_output::<_, Self>(std::any::Any::type_id(&<MockCat as Cat>::new), "<MockCat as Cat>::new", (<String>::clone(&name),)) {
+                    if let Some(out) = mry::static_record_call_and_find_mock_output::<_, Self>(std::any::Any::type_id(&<MockCat as Cat>::new), "<MockCat as Cat>::new", (<String>::clone(&name),), mry::__fallback!(Self)) {
                         return out;
                     }
                     panic!("mock not found for Cat")
@@ -442,6 +869,23 @@ mod test {
                     )
                 }
             }
+
+            #[cfg(debug_assertions)]
+            #[doc(hidden)]
+            #[allow(unused_macros)]
+            macro_rules! __mry_supertrait_Cat {
+                ($mock:ident [$($handle:ident)?], $($trait_:tt)*) => {
+                    mry::__mock_supertrait!({ } {
+                        trait Cat {
+                            fn new(name: String) -> Self;
+                        }
+                    } $mock [$($handle)?], $($trait_)*);
+                };
+            }
+            #[cfg(debug_assertions)]
+            #[doc(hidden)]
+            #[allow(unused_imports)]
+            pub(crate) use __mry_supertrait_Cat;
         }.to_string());
     }
 
@@ -473,12 +917,28 @@ mod test {
                     pub mry : mry::Mry,
                 }
 
+                #[cfg(debug_assertions)]
+                impl MockCat {
+                    /// Creates a mock that returns `Default::default()` from methods with no matching rule.
+                    pub fn nice() -> Self {
+                        Self {
+                            mry: mry::Mry::nice(),
+                        }
+                    }
+                    /// Creates a mock with a name that appears in panic and assertion messages.
+                    pub fn named(name: impl Into<String>) -> Self {
+                        let mut mock = Self::default();
+                        mock.mry.set_name(name);
+                        mock
+                    }
+                }
+
                 #[cfg(debug_assertions)]
                 impl Cat for MockCat {
                     #[cfg_attr(debug_assertions, track_caller)]
                     fn not_skipped(&self) -> String {
                         #[cfg(debug_assertions)]
-                        if let Some(out) = self.mry.record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&<MockCat as Cat>::not_skipped), "Cat::not_skipped", ()) {
+                        if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&<MockCat as Cat>::not_skipped), "Cat::not_skipped", (), mry::__fallback!(String)).output_with(self) {
                             return out;
                         }
                         panic!("mock not found for Cat")
@@ -503,6 +963,24 @@ mod test {
                         )
                     }
                 }
+
+                #[cfg(debug_assertions)]
+                #[doc(hidden)]
+                #[allow(unused_macros)]
+                macro_rules! __mry_supertrait_Cat {
+                    ($mock:ident [$($handle:ident)?], $($trait_:tt)*) => {
+                        mry::__mock_supertrait!({ } {
+                            trait Cat {
+                                fn not_skipped(&self) -> String;
+                                fn skipped(&self, rc: Rc<String>) -> String;
+                            }
+                        } $mock [$($handle)?], $($trait_)*);
+                    };
+                }
+                #[cfg(debug_assertions)]
+                #[doc(hidden)]
+                #[allow(unused_imports)]
+                pub(crate) use __mry_supertrait_Cat;
             }
             .to_string()
         );
@@ -534,12 +1012,28 @@ mod test {
                     pub mry : mry::Mry,
                 }
 
+                #[cfg(debug_assertions)]
+                impl MockCat {
+                    /// Creates a mock that returns `Default::default()` from methods with no matching rule.
+                    pub fn nice() -> Self {
+                        Self {
+                            mry: mry::Mry::nice(),
+                        }
+                    }
+                    /// Creates a mock with a name that appears in panic and assertion messages.
+                    pub fn named(name: impl Into<String>) -> Self {
+                        let mut mock = Self::default();
+                        mock.mry.set_name(name);
+                        mock
+                    }
+                }
+
                 #[cfg(debug_assertions)]
                 impl Cat for MockCat {
                     #[cfg_attr(debug_assertions, track_caller)]
                     fn meow(&self, count: usize) -> String {
                         #[cfg(debug_assertions)]
-                        if let Some(out) = self.mry.record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&<MockCat as Cat>::meow), "Cat::meow", (<usize>::clone(&count),)) {
+                        if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&<MockCat as Cat>::meow), "Cat::meow", (<usize>::clone(&count),), mry::__fallback!(String)).output_with(self) {
                             return out;
                         }
                         (move || {
@@ -562,8 +1056,299 @@ mod test {
                         )
                     }
                 }
+
+                #[cfg(debug_assertions)]
+                #[doc(hidden)]
+                #[allow(unused_macros)]
+                macro_rules! __mry_supertrait_Cat {
+                    ($mock:ident [$($handle:ident)?], $($trait_:tt)*) => {
+                        mry::__mock_supertrait!({ } {
+                            trait Cat {
+                                fn meow(&self, count: usize) -> String {
+                                    "default meow".to_string()
+                                }
+                            }
+                        } $mock [$($handle)?], $($trait_)*);
+                    };
+                }
+                #[cfg(debug_assertions)]
+                #[doc(hidden)]
+                #[allow(unused_imports)]
+                pub(crate) use __mry_supertrait_Cat;
             }
             .to_string()
         );
     }
+
+    #[test]
+    fn fallback_is_applied_on_construction() {
+        let attr = MryAttr::from_meta(&parse_quote! {
+            mry(fallback = "default")
+        })
+        .unwrap();
+
+        let input: ItemTrait = parse_quote! {
+            trait Cat {
+                fn meow(&self) -> String;
+            }
+        };
+
+        assert_eq!(
+            transform(&attr, input).to_string(),
+            quote! {
+                trait Cat {
+                    fn meow(&self) -> String;
+                }
+
+                #[cfg(debug_assertions)]
+                #[derive(Clone, Debug)]
+                struct MockCat {
+                    pub mry : mry::Mry,
+                }
+
+                #[cfg(debug_assertions)]
+                impl Default for MockCat {
+                    fn default() -> Self {
+                        Self {
+                            mry: mry::Mry::with_fallback(mry::Fallback::Default),
+                        }
+                    }
+                }
+
+                #[cfg(debug_assertions)]
+                impl MockCat {
+                    /// Creates a mock that returns `Default::default()` from methods with no matching rule.
+                    pub fn nice() -> Self {
+                        Self {
+                            mry: mry::Mry::nice(),
+                        }
+                    }
+                    /// Creates a mock with a name that appears in panic and assertion messages.
+                    pub fn named(name: impl Into<String>) -> Self {
+                        let mut mock = Self::default();
+                        mock.mry.set_name(name);
+                        mock
+                    }
+                }
+
+                #[cfg(debug_assertions)]
+                impl Cat for MockCat {
+                    #[cfg_attr(debug_assertions, track_caller)]
+                    fn meow(&self) -> String {
+                        #[cfg(debug_assertions)]
+                        if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&<MockCat as Cat>::meow), "Cat::meow", (), mry::__fallback!(String, Some(mry::Fallback::Default))).output_with(self) {
+                            return out;
+                        }
+                        panic!("mock not found for Cat")
+                    }
+                }
+
+                #[cfg(debug_assertions)]
+                impl MockCat {
+                    #[cfg(debug_assertions)]
+                    #[must_use]
+                    pub fn mock_meow(&mut self,) -> mry::MockLocator<(), String, String, mry::Behavior0<(), String> > {
+                        mry::MockLocator::new(
+                            self.mry.mocks(),
+                            std::any::Any::type_id(&<MockCat as Cat>::meow),
+                            "Cat::meow",
+                            ().into(),
+                            std::convert::identity,
+                        )
+                    }
+                }
+
+                #[cfg(debug_assertions)]
+                #[doc(hidden)]
+                #[allow(unused_macros)]
+                macro_rules! __mry_supertrait_Cat {
+                    ($mock:ident [$($handle:ident)?], $($trait_:tt)*) => {
+                        mry::__mock_supertrait!({ } {
+                            trait Cat {
+                                fn meow(&self) -> String;
+                            }
+                        } $mock [$($handle)?], $($trait_)*);
+                    };
+                }
+                #[cfg(debug_assertions)]
+                #[doc(hidden)]
+                #[allow(unused_imports)]
+                pub(crate) use __mry_supertrait_Cat;
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn clone_policy_implements_clone() {
+        let attr = MryAttr::from_meta(&parse_quote! {
+            mry(clone = "fresh")
+        })
+        .unwrap();
+
+        let input: ItemTrait = parse_quote! {
+            trait Cat {}
+        };
+
+        let output = transform(&attr, input).to_string();
+        assert!(output.contains(
+            &quote! {
+                #[cfg(debug_assertions)]
+                #[derive(Default, Debug)]
+                struct MockCat {
+                    pub mry: mry::Mry,
+                }
+
+                #[cfg(debug_assertions)]
+                impl Clone for MockCat {
+                    fn clone(&self) -> Self {
+                        Self::default()
+                    }
+                }
+            }
+            .to_string()
+        ));
+    }
+
+    #[test]
+    fn supertraits() {
+        let attr = MryAttr::from_meta(&parse_quote! {
+            mry(supertraits(Named, a::Base))
+        })
+        .unwrap();
+
+        let input: ItemTrait = parse_quote! {
+            trait Cat: Named + a::Base {}
+        };
+
+        let output = transform(&attr, input).to_string();
+        assert!(output.contains(
+            &quote! {
+                #[cfg(debug_assertions)]
+                __mry_supertrait_Named!(MockCat [], Named);
+                #[cfg(debug_assertions)]
+                a::__mry_supertrait_Base!(MockCat [], a::Base);
+            }
+            .to_string()
+        ));
+    }
+
+    #[test]
+    fn implements_supertrait_for_subtrait_mock() {
+        let input: Supertrait = parse_quote! {
+            { } {
+                trait Named {
+                    fn name(&self) -> String;
+                }
+            } MockCat [MockCatHandle], a::Named
+        };
+
+        assert_eq!(
+            transform_supertrait(&MryAttr::default(), input).to_string(),
+            quote! {
+                #[cfg(debug_assertions)]
+                impl a::Named for MockCat {
+                    #[cfg_attr(debug_assertions, track_caller)]
+                    fn name(&self) -> String {
+                        #[cfg(debug_assertions)]
+                        if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&<MockCat as a::Named>::name), "Named::name", (), mry::__fallback!(String)).output_with(self) {
+                            return out;
+                        }
+                        panic!("mock not found for Named")
+                    }
+                }
+
+                #[cfg(debug_assertions)]
+                impl MockCat {
+                    #[cfg(debug_assertions)]
+                    #[must_use]
+                    pub fn mock_name(&mut self,) -> mry::MockLocator<(), String, String, mry::Behavior0<(), String> > {
+                        mry::MockLocator::new(
+                            self.mry.mocks(),
+                            std::any::Any::type_id(&<MockCat as a::Named>::name),
+                            "Named::name",
+                            ().into(),
+                            std::convert::identity,
+                        )
+                    }
+                }
+
+                #[cfg(debug_assertions)]
+                impl MockCatHandle {
+                    #[cfg(debug_assertions)]
+                    #[must_use]
+                    pub fn mock_name(&mut self,) -> mry::MockLocator<(), String, String, mry::Behavior0<(), String> > {
+                        mry::MockLocator::new(
+                            self.mry.mocks(),
+                            std::any::Any::type_id(&<MockCat as a::Named>::name),
+                            "Named::name",
+                            ().into(),
+                            std::convert::identity,
+                        )
+                    }
+                }
+            }
+            .to_string()
+        );
+    }
+    #[test]
+    fn handle_is_opted_in() {
+        let attr = MryAttr::from_meta(&parse_quote! {
+            mry(handle = "controller")
+        })
+        .unwrap();
+        let input: ItemTrait = parse_quote! {
+            pub trait Cat {
+                fn handle(&self);
+            }
+        };
+
+        let output = transform(&attr, input).to_string();
+        assert!(output.contains(
+            &quote! {
+                pub fn controller(&self) -> MockCatController {
+                    MockCatController {
+                        mry: self.mry.share(),
+                    }
+                }
+            }
+            .to_string()
+        ));
+        assert!(output.contains(
+            &quote! {
+                #[cfg(debug_assertions)]
+                #[derive(Debug)]
+                pub struct MockCatController {
+                    pub mry: mry::Mry,
+                }
+            }
+            .to_string()
+        ));
+        assert!(output.contains(&quote![impl MockCatController].to_string()));
+        assert!(!transform(
+            &MryAttr::default(),
+            parse_quote!(
+                trait Cat {}
+            )
+        )
+        .to_string()
+        .contains("Handle"));
+    }
+
+    #[test]
+    fn handle_conflicting_with_method() {
+        let attr = MryAttr::from_meta(&parse_quote! {
+            mry(handle)
+        })
+        .unwrap();
+        let input: ItemTrait = parse_quote! {
+            trait Cat {
+                fn handle(&self);
+            }
+        };
+
+        assert!(transform(&attr, input)
+            .to_string()
+            .contains("compile_error"));
+    }
 }
@@ -1,6 +1,7 @@
 mod attrs;
 mod create_behaviors;
 mod create_matchers;
+mod create_mock_fns;
 mod item_fn;
 mod item_impl;
 mod item_struct;
@@ -8,6 +9,7 @@ mod item_trait;
 mod lock;
 mod method;
 mod new;
+mod test;
 use attrs::MryAttr;
 use darling::ast::NestedMeta;
 use darling::FromMeta;
@@ -32,7 +34,10 @@ pub fn mry(
     attr: proc_macro::TokenStream,
     input: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
-    let attr = MryAttr::from_list(&NestedMeta::parse_meta_list(attr.into()).unwrap()).unwrap();
+    let attr_tokens: TokenStream = attr.into();
+    let mut attr =
+        MryAttr::from_list(&NestedMeta::parse_meta_list(attr_tokens.clone()).unwrap()).unwrap();
+    attr.tokens = attr_tokens;
     match parse(input.clone())
         .map(TargetItem::Struct)
         .or_else(|_| parse(input.clone()).map(TargetItem::Impl))
@@ -41,7 +46,7 @@ pub fn mry(
     {
         Ok(target) => {
             let token_stream = match target {
-                TargetItem::Struct(target) => item_struct::transform(target),
+                TargetItem::Struct(target) => item_struct::transform(&attr, target),
                 TargetItem::Impl(target) => item_impl::transform(&attr, target),
                 TargetItem::Trait(target) => item_trait::transform(&attr, target),
                 TargetItem::Fn(target) => item_fn::transform(&attr, target),
@@ -57,7 +62,12 @@ pub fn mry(
 
 #[proc_macro]
 pub fn new(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    new::transform(parse_macro_input!(input as ExprStruct)).into()
+    new::transform(parse_macro_input!(input as new::New)).into()
+}
+
+#[proc_macro]
+pub fn spy(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    new::transform_spy(parse_macro_input!(input as ExprStruct)).into()
 }
 
 #[proc_macro]
@@ -70,6 +80,20 @@ pub fn create_matchers(_: proc_macro::TokenStream) -> proc_macro::TokenStream {
     create_matchers::create().into()
 }
 
+#[proc_macro]
+pub fn create_mock_fns(_: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    create_mock_fns::create().into()
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __mock_supertrait(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as item_trait::Supertrait);
+    let attr =
+        MryAttr::from_list(&NestedMeta::parse_meta_list(input.attr.clone()).unwrap()).unwrap();
+    item_trait::transform_supertrait(&attr, input).into()
+}
+
 #[proc_macro_attribute]
 pub fn lock(
     attribute: proc_macro::TokenStream,
@@ -82,6 +106,21 @@ pub fn lock(
     .into()
 }
 
+#[proc_macro_attribute]
+pub fn test(
+    attribute: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let attr = match NestedMeta::parse_meta_list(attribute.into())
+        .map_err(darling::Error::from)
+        .and_then(|list| test::TestAttr::from_list(&list))
+    {
+        Ok(attr) => attr,
+        Err(err) => return err.write_errors().into(),
+    };
+    test::transform(&attr, parse_macro_input!(input as ItemFn)).into()
+}
+
 struct M {
     mry_attr: MryAttr,
     tokens: TokenStream,
@@ -92,7 +131,7 @@ impl VisitMut for M {
         item_trait::transform(&self.mry_attr, i.clone()).to_tokens(&mut self.tokens)
     }
     fn visit_item_struct_mut(&mut self, i: &mut ItemStruct) {
-        item_struct::transform(i.clone()).to_tokens(&mut self.tokens)
+        item_struct::transform(&self.mry_attr, i.clone()).to_tokens(&mut self.tokens)
     }
     fn visit_item_impl_mut(&mut self, i: &mut ItemImpl) {
         item_impl::transform(&self.mry_attr, i.clone()).to_tokens(&mut self.tokens)
@@ -2,7 +2,7 @@ use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 use syn::{parse_quote, ItemFn};
 
-pub struct LockPaths(Vec<syn::Type>);
+pub struct LockPaths(pub Vec<syn::Type>);
 
 impl syn::parse::Parse for LockPaths {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
@@ -1,11 +1,13 @@
-use proc_macro2::{Span, TokenStream};
+use proc_macro2::{Group, Span, TokenStream, TokenTree};
 use quote::{format_ident, quote};
 use syn::{
-    parse_quote, punctuated::Punctuated, Attribute, FnArg, GenericParam, Ident, Pat, PatIdent,
-    ReturnType, Signature, Type, TypeArray, TypeSlice, Visibility,
+    parse_quote, punctuated::Punctuated, visit::Visit, Attribute, ExprClosure, FnArg,
+    GenericArgument, GenericParam, Generics, Ident, Lifetime, Pat, PatIdent, PathArguments,
+    ReturnType, Signature, Token, Type, TypeArray, TypeParamBound, TypeSlice, Visibility,
+    WherePredicate,
 };
 
-use crate::attrs::MryAttr;
+use crate::attrs::{FallbackPolicy, MryAttr};
 
 fn has_track_caller_attr(attrs: &[Attribute]) -> bool {
     attrs.iter().any(|attr| {
@@ -23,6 +25,14 @@ fn has_track_caller_attr(attrs: &[Attribute]) -> bool {
     })
 }
 
+// `#[track_caller]` is only allowed on functions with the Rust ABI.
+fn is_rust_abi(sig: &Signature) -> bool {
+    match &sig.abi {
+        None => true,
+        Some(abi) => abi.name.as_ref().is_some_and(|name| name.value() == "Rust"),
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn transform(
     mry_attr: &MryAttr,
@@ -30,20 +40,59 @@ pub(crate) fn transform(
     method_prefix: TokenStream, // `Self::`
     method_debug_prefix: &str,  // "Cat::"
     record_call_and_find_mock_output: TokenStream,
+    // Lends the receiver to the behavior with `self.mry.record_call(..).output_with(self)`.
+    // This requires `Self: 'static`.
+    lend_receiver: bool,
     vis: Option<&Visibility>,
     attrs: &[Attribute],
     sig: &Signature,
+    // Generics of the `impl` block or trait
+    outer_generics: &Generics,
     body: &TokenStream,
     // The body must not be wrapped in a closure even if the method has no `#[track_caller]`
     force_location_tracking: bool,
 ) -> (TokenStream, TokenStream) {
+    if let Some(constness) = &sig.constness {
+        // Stable Rust cannot tell a const evaluation from a runtime call, so the mock lookup
+        // cannot be placed in a `const fn` without breaking its const callers.
+        let error = syn::Error::new_spanned(
+            constness,
+            format!(
+                "mry cannot mock `const fn`. Remove `const` or skip it with `#[mry::mry(skip_fns({}))]`",
+                sig.ident
+            ),
+        )
+        .to_compile_error();
+        return (
+            quote! {
+                #error
+                #(#attrs)*
+                #vis #sig {
+                    #body
+                }
+            },
+            TokenStream::default(),
+        );
+    }
     // Split into receiver and other inputs
     let mut receiver = None;
     let mut mock_receiver = None;
     let mut inputs = sig.inputs.iter().peekable();
     // If receiver exists
+    let mut output_with_receiver = TokenStream::default();
     if let Some(FnArg::Receiver(rec)) = inputs.peek() {
         receiver = Some(FnArg::Receiver(rec.clone()));
+        output_with_receiver = if !lend_receiver {
+            TokenStream::default()
+        } else if rec.colon_token.is_some() {
+            quote![.output_with(&*self)]
+        } else if rec.reference.is_some() && rec.mutability.is_some() {
+            quote![.output_with(&mut *self)]
+        } else if rec.reference.is_some() {
+            quote![.output_with(self)]
+        } else {
+            quote![.output_with(&self)]
+        };
         mock_receiver = Some(quote![&mut self,]);
         // Skip the receiver
         inputs.next();
@@ -89,16 +138,21 @@ pub(crate) fn transform(
         static_type: static_output_type,
         behavior_type: behavior_output_type,
         send_wrapper: out_is_send_wrapper,
+        boxed: boxed_output,
     } = match &sig.output {
         ReturnType::Default => OutputType {
             static_type: quote!(()),
             behavior_type: quote!(()),
             send_wrapper: false,
+            boxed: None,
         },
         ReturnType::Type(_, ty) => {
             if let Some(output) = impl_future(ty) {
                 is_impl_future = true;
-                make_output_type(mry_attr, output)
+                OutputType {
+                    boxed: None,
+                    ..make_output_type(mry_attr, output)
+                }
             } else {
                 make_output_type(mry_attr, ty)
             }
@@ -127,16 +181,11 @@ pub(crate) fn transform(
         Span::call_site(),
     );
     struct Arg {
-        org_ty: Type,
-        owned_ty: Option<Type>,
+        /// The type recorded for the argument
+        ty: Type,
         to_owned: TokenStream,
         name: Ident,
     }
-    impl Arg {
-        fn ty(&self) -> &Type {
-            self.owned_ty.as_ref().unwrap_or(&self.org_ty)
-        }
-    }
     let args: Vec<Arg> = inputs_without_receiver
         .iter()
         .enumerate()
@@ -150,18 +199,41 @@ pub(crate) fn transform(
             } else {
                 format_ident!("arg{}", index)
             };
-            let (owned_ty, to_owned) = make_owned_type(mry_attr, &name, &org_ty);
-            Some(Arg {
-                org_ty,
-                owned_ty: Some(owned_ty),
-                to_owned,
-                name,
-            })
+            let (ty, to_owned) = match (
+                mry_attr.by_ref_arg(&sig.ident, &org_ty),
+                mry_attr.arg_into(&name),
+            ) {
+                (Some(projection), _) => make_by_ref_type(&name, &org_ty, projection),
+                (None, Some(_)) if receiver.is_none() && is_impl_trait(&org_ty) => (
+                    parse_quote!(()),
+                    syn::Error::new_spanned(
+                        &org_ty,
+                        "mry cannot lock a function with `impl Trait` arguments. Use a generic parameter instead",
+                    )
+                    .to_compile_error(),
+                ),
+                (None, Some(target)) => (
+                    target.clone(),
+                    make_into_type(&name, &org_ty, target, &sig.generics),
+                ),
+                (None, None) if is_impl_trait(&org_ty) => (
+                    parse_quote!(()),
+                    syn::Error::new_spanned(
+                        &org_ty,
+                        format!(
+                            "mry cannot record `impl Trait` argument `{name}`. Declare the recorded type like `#[mry::mry(arg_into({name} = Vec<u8>))]`, or skip it with `skip_args`"
+                        ),
+                    )
+                    .to_compile_error(),
+                ),
+                (None, None) => make_owned_type(mry_attr, &name, &org_ty),
+            };
+            Some(Arg { ty, to_owned, name })
         })
         .collect();
     let mock_args = args.iter().map(|arg| {
         let name = &arg.name;
-        let ty = arg.ty().clone();
+        let ty = &arg.ty;
         quote! {
             #name: impl Into<mry::ArgMatcher<#ty>>
         }
@@ -172,16 +244,11 @@ pub(crate) fn transform(
             #name.into()
         }
     });
-    let input_types = args.iter().map(|arg| arg.ty()).collect::<Vec<_>>();
-    let owned_args = args.iter().map(|arg| {
-        let name = &arg.name;
-        let to_owned = &arg.to_owned;
-        if arg.owned_ty.is_some() {
-            quote![#to_owned]
-        } else {
-            quote![#name]
-        }
-    });
+    let has_by_ref_args = inputs_without_receiver
+        .iter()
+        .any(|(input, skip)| !*skip && mry_attr.by_ref_arg(&sig.ident, &input.ty).is_some());
+    let input_types = args.iter().map(|arg| &arg.ty).collect::<Vec<_>>();
+    let owned_args = args.iter().map(|arg| &arg.to_owned);
     let behavior_type = quote![mry::#behavior_name<(#(#input_types,)*), #behavior_output_type>];
     let allow_non_snake_case_or_blank = if ident.to_string().starts_with('_') {
         quote!(#[allow(non_snake_case)])
@@ -203,10 +270,56 @@ pub(crate) fn transform(
         })
         .collect();
 
-    let key = if non_lifetime_generics.is_empty() {
-        quote![std::any::Any::type_id(&#method_prefix #ident)]
+    // Generic parameters only used by arguments of `arg_into` are dropped from the mock function,
+    // so that the rules are shared by all of them. Functions without receivers keep them, because
+    // `mry::lock` names the function with them.
+    let into_generics: Vec<(&Ident, &Type)> = non_lifetime_generics
+        .iter()
+        .filter(|_| receiver.is_some())
+        .filter_map(|param| {
+            let mut into = None;
+            for (input, skip) in &inputs_without_receiver {
+                if *skip || !mentions(&input.ty, param) {
+                    continue;
+                }
+                let target = match &*input.pat {
+                    Pat::Ident(ident) => mry_attr.arg_into(&ident.ident),
+                    _ => None,
+                };
+                let is_param = match input.ty.as_ref() {
+                    Type::Reference(ty) => is_ident(&ty.elem, param),
+                    ty => is_ident(ty, param),
+                };
+                match target {
+                    Some(target) if is_param => into = into.or(Some(target)),
+                    _ => return None,
+                }
+            }
+            if let ReturnType::Type(_, ty) = &sig.output {
+                if mentions(ty, param) {
+                    return None;
+                }
+            }
+            into.map(|target| (*param, target))
+        })
+        .collect();
+    // The function cannot be named with `impl Trait` arguments or the dropped parameters, so it
+    // is keyed by its path instead.
+    let key = if into_generics.is_empty()
+        && !inputs_without_receiver
+            .iter()
+            .any(|(input, _)| is_impl_trait(&input.ty))
+    {
+        if non_lifetime_generics.is_empty() {
+            quote![std::any::Any::type_id(&#method_prefix #ident)]
+        } else {
+            quote![std::any::Any::type_id(&#method_prefix #ident::<#(#non_lifetime_generics),*>)]
+        }
     } else {
-        quote![std::any::Any::type_id(&#method_prefix #ident::<#(#non_lifetime_generics),*>)]
+        let generics = non_lifetime_generics
+            .iter()
+            .filter(|param| !into_generics.iter().any(|(ident, _)| ident == *param));
+        quote![std::any::TypeId::of::<mry::NamedKey<(#(#generics,)*), { mry::named_key_hash(concat!(module_path!(), "::", #name)) }>>()]
     };
 
     let mut sig = sig.clone();
@@ -215,8 +328,23 @@ pub(crate) fn transform(
             .into_iter()
             .chain(args_without_receiver.iter().cloned().map(FnArg::Typed)),
     );
-    let generics = &sig.generics;
-    let where_clause = &sig.generics.where_clause;
+    let mut generics = sig.generics.clone();
+    if !into_generics.is_empty() {
+        let is_into = |ident: &Ident| into_generics.iter().any(|(param, _)| *param == ident);
+        generics.params =
+            Punctuated::from_iter(generics.params.into_iter().filter(
+                |param| !matches!(param, GenericParam::Type(param) if is_into(&param.ident)),
+            ));
+        if let Some(where_clause) = &mut generics.where_clause {
+            where_clause.predicates = Punctuated::from_iter(
+                where_clause.predicates.clone().into_iter().filter(|predicate| {
+                    !matches!(predicate, WherePredicate::Type(predicate) if into_generics.iter().any(|(param, _)| is_ident(&predicate.bounded_ty, param)))
+                }),
+            );
+        }
+    }
+    let generics = &generics;
+    let where_clause = &generics.where_clause;
 
     let out = if out_is_send_wrapper {
         quote!(mry::send_wrapper::SendWrapper::take(out))
@@ -237,35 +365,87 @@ pub(crate) fn transform(
     };
 
     let has_track_caller_attr = has_track_caller_attr(attrs);
-    let track_caller_attr = if has_track_caller_attr {
+    let rust_abi = is_rust_abi(&sig);
+    let track_caller_attr = if has_track_caller_attr || !rust_abi {
         quote!()
     } else {
         quote!(#[cfg_attr(debug_assertions, track_caller)])
     };
 
     // This ensures that the panic within the body is located at the correct line even if the method itself is not marked with `#[track_caller]`
-    let body = if has_track_caller_attr || force_location_tracking {
-        // If the method itself is marked with `#[track_caller]`, we can just use the body as is
-        body.clone()
+    let body = if has_track_caller_attr || force_location_tracking || !rust_abi {
+        // If the method itself is marked with `#[track_caller]` or cannot be marked with it, we can just use the body as is
+        match &boxed_output {
+            // A body without the default of a trait method only panics
+            Some(_) if force_location_tracking => quote! {
+                #[allow(clippy::diverging_sub_expression)]
+                let out: #behavior_output_type = #body;
+                #[allow(unreachable_code)]
+                return out;
+            },
+            Some(boxed) => quote! {
+                #boxed((move || { #body })())
+            },
+            None => body.clone(),
+        }
     } else if sig.asyncness.is_some() {
         quote! {
             (move || async move { #body })().await
         }
+    } else if let Some(boxed) = &boxed_output {
+        quote! {
+            #boxed((move || { #body })())
+        }
     } else {
         quote! {
             (move || { #body })()
         }
     };
 
+    // `DefaultOutput` cannot see a `Default` bound of a generic output, so require it instead
+    let generic_output = match &sig.output {
+        ReturnType::Default => false,
+        ReturnType::Type(_, ty) => {
+            sig.generics
+                .type_params()
+                .chain(outer_generics.type_params())
+                .any(|param| mentions(ty, &param.ident))
+                || (outer_generics.type_params().next().is_some()
+                    && mentions(ty, &format_ident!("Self")))
+        }
+    };
+    let fallback = match &mry_attr.fallback {
+        Some(FallbackPolicy::Default) if generic_output => {
+            quote![mry::__fallback!(#static_output_type, default)]
+        }
+        Some(policy) => quote![mry::__fallback!(#static_output_type, Some(#policy))],
+        None => quote![mry::__fallback!(#static_output_type)],
+    };
+
+    let find_mock_output = quote! {
+        if let Some(out) = #record_call_and_find_mock_output::<_, #static_output_type>(#key, #name, (#(#owned_args,)*), #fallback)#output_with_receiver {
+            #return_out
+        }
+    };
+    // Arguments matched by reference are lent only until the mock lookup ends
+    let find_mock_output = if has_by_ref_args {
+        quote! {
+            {
+                let mut __mry_borrows = mry::Borrows::default();
+                #find_mock_output
+            }
+        }
+    } else {
+        find_mock_output
+    };
+
     (
         quote! {
             #(#attrs)*
             #track_caller_attr
             #vis #sig {
                 #[cfg(debug_assertions)]
-                if let Some(out) = #record_call_and_find_mock_output::<_, #static_output_type>(#key, #name, (#(#owned_args,)*)) {
-                    #return_out
-                }
+                #find_mock_output
                 #(#bindings)*
                 #body
             }
@@ -290,9 +470,22 @@ pub(crate) fn transform(
 }
 
 pub fn make_owned_type(mry_attr: &MryAttr, name: &Ident, ty: &Type) -> (Type, TokenStream) {
+    let (borrowed, borrow) = match ty {
+        Type::Reference(reference) => (reference.elem.as_ref(), quote![&*#name]),
+        ty => (ty, quote![&#name]),
+    };
+    if let Some(owned) = mry_attr.owned(borrowed) {
+        return (owned.clone(), quote![<#owned as From<&_>>::from(#borrow)]);
+    }
     if is_str(ty) {
         return (parse_quote!(String), quote![#name.to_string()]);
     }
+    if let Some(unsized_ty) = to_owned_type(borrowed) {
+        return (
+            parse_quote!(<#unsized_ty as ToOwned>::Owned),
+            quote![<#unsized_ty as ToOwned>::to_owned(&#name)],
+        );
+    }
     fn make_owned_type_slice(mry_attr: &MryAttr, name: &Ident, elem: &Type) -> (Type, TokenStream) {
         let map_ident = Ident::new("elem", Span::call_site());
         let (inner_owned, inner_clone) = make_owned_type(mry_attr, &map_ident, elem);
@@ -326,6 +519,174 @@ pub fn make_owned_type(mry_attr: &MryAttr, name: &Ident, ty: &Type) -> (Type, To
     (owned.clone(), cloned)
 }
 
+/// The unsized type recorded with `ToOwned`, for `&Path`, `&OsStr`, `&CStr`, and `Cow<'_, B>`
+fn to_owned_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    match &segment.arguments {
+        PathArguments::None
+            if ["Path", "OsStr", "CStr"].contains(&segment.ident.to_string().as_str()) =>
+        {
+            Some(ty)
+        }
+        PathArguments::AngleBracketed(args) if segment.ident == "Cow" => {
+            args.args.iter().find_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Makes `mry::ByRef` that borrows the argument during the mock lookup and records the projection
+fn make_by_ref_type(
+    name: &Ident,
+    ty: &Type,
+    projection: Option<&ExprClosure>,
+) -> (Type, TokenStream) {
+    let (target, borrow) = match ty {
+        Type::Reference(ty) => (ty.elem.as_ref().clone(), quote![&*#name]),
+        ty => (ty.clone(), quote![&#name]),
+    };
+    // A matcher could keep what it borrows from the argument if its lifetimes were `'static`
+    if let Some(lifetime) = non_static_lifetime(&target) {
+        return (
+            parse_quote!(()),
+            syn::Error::new_spanned(
+                lifetime,
+                "mry cannot match an argument with lifetimes by reference. `by_ref` takes only `'static` types",
+            )
+            .to_compile_error(),
+        );
+    }
+    let (projection_ty, projection) = match projection {
+        Some(closure) => {
+            let ReturnType::Type(_, projection_ty) = &closure.output else {
+                unreachable!("checked by the attribute")
+            };
+            (projection_ty.as_ref().clone(), quote![(#closure)(#borrow)])
+        }
+        None => (parse_quote!(()), quote![()]),
+    };
+    (
+        parse_quote!(mry::ByRef<#target, #projection_ty>),
+        quote![__mry_borrows.lend::<#target, _>(#borrow, #projection)],
+    )
+}
+
+/// Converts an `impl Trait` or generic argument to the type declared by `arg_into` with its bounds
+fn make_into_type(name: &Ident, ty: &Type, target: &Type, generics: &Generics) -> TokenStream {
+    let (inner, arg) = match ty {
+        Type::Reference(ty) => (ty.elem.as_ref(), quote![#name]),
+        ty => (ty, quote![&#name]),
+    };
+    let mut bound_lists: Vec<&Punctuated<TypeParamBound, syn::Token![+]>> = Vec::new();
+    match inner {
+        Type::ImplTrait(impl_trait) => bound_lists.push(&impl_trait.bounds),
+        ty => {
+            for param in generics.type_params() {
+                if is_ident(ty, &param.ident) {
+                    bound_lists.push(&param.bounds);
+                }
+            }
+            for predicate in generics.where_clause.iter().flat_map(|w| &w.predicates) {
+                if let WherePredicate::Type(predicate) = predicate {
+                    if &predicate.bounded_ty == ty {
+                        bound_lists.push(&predicate.bounds);
+                    }
+                }
+            }
+        }
+    }
+    let bounds = bound_lists
+        .into_iter()
+        .flatten()
+        .filter_map(|bound| match bound {
+            TypeParamBound::Trait(bound) => Some(bound),
+            _ => None,
+        });
+    // The trait and its first type argument like `AsRef` and `[u8]`
+    let traits: Vec<(String, Option<&Type>)> = bounds
+        .filter_map(|bound| {
+            let segment = bound.path.segments.last()?;
+            let arg = match &segment.arguments {
+                PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+                    GenericArgument::Type(ty) => Some(ty),
+                    _ => None,
+                }),
+                _ => None,
+            };
+            Some((segment.ident.to_string(), arg))
+        })
+        .collect();
+    let find = |name: &str| {
+        traits
+            .iter()
+            .find(|(ident, _)| ident == name)
+            .map(|(_, arg)| *arg)
+    };
+    let converted = if let Some(Some(x)) = find("AsRef") {
+        quote![<#x as ToOwned>::to_owned(AsRef::<#x>::as_ref(#arg))]
+    } else if let Some(Some(x)) = find("Borrow") {
+        quote![<#x as ToOwned>::to_owned(std::borrow::Borrow::<#x>::borrow(#arg))]
+    } else if let (Some(Some(x)), Some(_)) = (find("Into"), find("Clone")) {
+        quote![Into::<#x>::into(Clone::clone(#arg))]
+    } else if find("ToString").is_some() || find("Display").is_some() {
+        quote![ToString::to_string(#arg)]
+    } else if find("Clone").is_some() {
+        quote![Clone::clone(#arg)]
+    } else {
+        return syn::Error::new_spanned(
+            ty,
+            format!("mry cannot convert `{name}` to the type of `arg_into`. It needs one of `AsRef`, `Borrow`, `Into` with `Clone`, `ToString`, `Display`, or `Clone` bounds"),
+        )
+        .to_compile_error();
+    };
+    quote![Into::<#target>::into(#converted)]
+}
+
+fn is_impl_trait(ty: &Type) -> bool {
+    match ty {
+        Type::ImplTrait(_) => true,
+        Type::Reference(ty) => matches!(*ty.elem, Type::ImplTrait(_)),
+        _ => false,
+    }
+}
+
+fn is_ident(ty: &Type, ident: &Ident) -> bool {
+    matches!(ty, Type::Path(path) if path.qself.is_none() && path.path.is_ident(ident))
+}
+
+/// Whether the type mentions the generic parameter
+fn mentions(ty: &Type, ident: &Ident) -> bool {
+    struct Mentions<'a>(&'a Ident, bool);
+    impl<'ast> Visit<'ast> for Mentions<'_> {
+        fn visit_ident(&mut self, ident: &'ast Ident) {
+            self.1 |= ident == self.0;
+        }
+    }
+    let mut visitor = Mentions(ident, false);
+    visitor.visit_type(ty);
+    visitor.1
+}
+
+fn non_static_lifetime(ty: &Type) -> Option<&Lifetime> {
+    struct NonStatic<'a>(Option<&'a Lifetime>);
+    impl<'ast> Visit<'ast> for NonStatic<'ast> {
+        fn visit_lifetime(&mut self, lifetime: &'ast Lifetime) {
+            if lifetime.ident != "static" {
+                self.0.get_or_insert(lifetime);
+            }
+        }
+    }
+    let mut visitor = NonStatic(None);
+    visitor.visit_type(ty);
+    visitor.0
+}
+
 pub fn impl_future(ty: &Type) -> Option<&Type> {
     let syn::Type::ImplTrait(impl_trait) = ty else {
         return None;
@@ -353,10 +714,13 @@ struct OutputType {
     static_type: TokenStream,
     behavior_type: TokenStream,
     send_wrapper: bool,
+    // Boxes the output of the body for `impl Trait`, so that it has the type of mocked outputs
+    boxed: Option<TokenStream>,
 }
 
 fn make_output_type(mry_attr: &MryAttr, ty: &Type) -> OutputType {
     let mut send_wrapper = mry_attr.test_non_send(ty);
+    let mut boxed = None;
     let ty = match ty {
         Type::Reference(ty) => {
             let ty = &ty.elem;
@@ -364,7 +728,14 @@ fn make_output_type(mry_attr: &MryAttr, ty: &Type) -> OutputType {
         }
         Type::ImplTrait(impl_trait) => {
             let bounds = &impl_trait.bounds;
-            quote!(Box<dyn #bounds>)
+            // `Box<dyn Stream>` is not a `Stream` unless it is `Unpin`.
+            if has_bound(bounds, "Stream") && !has_bound(bounds, "Unpin") {
+                boxed = Some(quote!(Box::pin));
+                quote!(std::pin::Pin<Box<dyn #bounds>>)
+            } else {
+                boxed = Some(quote!(Box::new));
+                quote!(Box<dyn #bounds>)
+            }
         }
         Type::Ptr(_) => {
             send_wrapper = true;
@@ -381,9 +752,37 @@ fn make_output_type(mry_attr: &MryAttr, ty: &Type) -> OutputType {
         static_type,
         behavior_type: quote!(#ty),
         send_wrapper,
+        boxed,
     }
 }
 
+fn has_bound(bounds: &Punctuated<TypeParamBound, Token![+]>, name: &str) -> bool {
+    bounds.iter().any(|bound| match bound {
+        TypeParamBound::Trait(bound) => bound
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == name),
+        _ => false,
+    })
+}
+
+/// Replaces `Self` in `mock_*` methods copied to a handle, like `Option<<Self as Iterator>::Item>`
+pub(crate) fn replace_self(tokens: TokenStream, ty: &TokenStream) -> TokenStream {
+    tokens
+        .into_iter()
+        .flat_map(|token| match token {
+            TokenTree::Ident(ident) if ident == "Self" => ty.clone(),
+            TokenTree::Group(group) => {
+                let mut replaced = Group::new(group.delimiter(), replace_self(group.stream(), ty));
+                replaced.set_span(group.span());
+                TokenTree::Group(replaced).into()
+            }
+            token => token.into(),
+        })
+        .collect()
+}
+
 pub fn is_str(ty: &Type) -> bool {
     match ty {
         Type::Reference(ty) => {
@@ -400,7 +799,7 @@ pub fn is_str(ty: &Type) -> bool {
 
 #[cfg(test)]
 mod test {
-    use crate::attrs::NotSend;
+    use crate::attrs::{NotSend, Owned};
 
     use super::*;
     use darling::FromMeta as _;
@@ -432,10 +831,12 @@ mod test {
             quote![self.mry.mocks()],
             quote![Self::],
             "Cat::",
-            quote![self.mry.record_call_and_find_mock_output],
+            quote![self.mry.record_call],
+            true,
             Some(&method.vis),
             &method.attrs,
             &method.sig,
+            &Generics::default(),
             &method
                 .block
                 .stmts
@@ -532,6 +933,47 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_make_owned_unsized() {
+        let ident = Ident::new("var", Span::call_site());
+        let path: Type = syn::parse_str("&std::path::Path").unwrap();
+        let (owned_type, converter) = make_owned_type(&MryAttr::default(), &ident, &path);
+        assert_eq!(
+            owned_type,
+            parse_quote!(<std::path::Path as ToOwned>::Owned)
+        );
+        assert_eq!(
+            remove_spaces(&converter.to_string()),
+            "<std::path::PathasToOwned>::to_owned(&var)"
+        );
+        let cow: Type = syn::parse_str("Cow<'_, str>").unwrap();
+        let (owned_type, converter) = make_owned_type(&MryAttr::default(), &ident, &cow);
+        assert_eq!(owned_type, parse_quote!(<str as ToOwned>::Owned));
+        assert_eq!(
+            remove_spaces(&converter.to_string()),
+            "<strasToOwned>::to_owned(&var)"
+        );
+    }
+
+    #[test]
+    fn test_make_owned_declared() {
+        let attr = MryAttr {
+            owned: Some(Owned(vec![(
+                parse_quote!(Request),
+                parse_quote!(OwnedRequest),
+            )])),
+            ..Default::default()
+        };
+        let ident = Ident::new("var", Span::call_site());
+        let a: Type = syn::parse_str("&Request<'a>").unwrap();
+        let (owned_type, converter) = make_owned_type(&attr, &ident, &a);
+        assert_eq!(owned_type, parse_quote!(OwnedRequest));
+        assert_eq!(
+            remove_spaces(&converter.to_string()),
+            "<OwnedRequestasFrom<&_>>::from(&*var)"
+        );
+    }
+
     #[test]
     fn test_make_owned_slice_of_non_send() {
         let attr = MryAttr {
@@ -602,6 +1044,38 @@ mod test {
         assert_eq!(output.behavior_type.to_string(), "* mut String");
     }
 
+    #[test]
+    fn test_make_output_stream() {
+        let stream: Type = parse_quote!(impl futures::Stream<Item = u8> + Send);
+        let unpin: Type = parse_quote!(impl Stream<Item = u8> + Send + Unpin);
+        let iterator: Type = parse_quote!(impl Iterator<Item = u8> + Send);
+
+        assert_eq!(
+            remove_spaces(
+                &make_output_type(&MryAttr::default(), &stream)
+                    .behavior_type
+                    .to_string()
+            ),
+            "std::pin::Pin<Box<dynfutures::Stream<Item=u8>+Send>>"
+        );
+        assert_eq!(
+            remove_spaces(
+                &make_output_type(&MryAttr::default(), &unpin)
+                    .behavior_type
+                    .to_string()
+            ),
+            "Box<dynStream<Item=u8>+Send+Unpin>"
+        );
+        assert_eq!(
+            remove_spaces(
+                &make_output_type(&MryAttr::default(), &iterator)
+                    .behavior_type
+                    .to_string()
+            ),
+            "Box<dynIterator<Item=u8>+Send>"
+        );
+    }
+
     #[test]
     fn test_make_static_with_non_send() {
         let attr = MryAttr {
@@ -659,7 +1133,7 @@ mod test {
                 #[cfg_attr(debug_assertions, track_caller)]
                 fn meow(&self, count: usize) -> String {
                     #[cfg(debug_assertions)]
-                    if let Some(out) = self.mry.record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<usize>::clone(&count),)) {
+                    if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<usize>::clone(&count),), mry::__fallback!(String)).output_with(self) {
                         return out;
                     }
                     (move || {
@@ -683,6 +1157,38 @@ mod test {
         );
     }
 
+    #[test]
+    fn lends_receiver() {
+        let mut_receiver: ImplItemFn = parse_quote! {
+            fn rename(&mut self, name: String) {
+                self.name = name;
+            }
+        };
+        let owned_receiver: ImplItemFn = parse_quote! {
+            fn into_name(self) -> String {
+                self.name
+            }
+        };
+        let box_receiver: ImplItemFn = parse_quote! {
+            fn boxed_name(self: Box<Self>) -> String {
+                self.name
+            }
+        };
+
+        assert!(t(&mut_receiver)
+            .0
+            .to_string()
+            .contains(&quote![.output_with(&mut *self)].to_string()));
+        assert!(t(&owned_receiver)
+            .0
+            .to_string()
+            .contains(&quote![.output_with(&self)].to_string()));
+        assert!(t(&box_receiver)
+            .0
+            .to_string()
+            .contains(&quote![.output_with(&*self)].to_string()));
+    }
+
     #[test]
     fn adds_allow_non_snake_case() {
         let input: ImplItemFn = parse2(quote! {
@@ -698,7 +1204,7 @@ mod test {
                 #[cfg_attr(debug_assertions, track_caller)]
                 fn _meow(&self, count: usize) -> String {
                     #[cfg(debug_assertions)]
-                    if let Some(out) = self.mry.record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&Self::_meow), "Cat::_meow", (<usize>::clone(&count),)) {
+                    if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&Self::_meow), "Cat::_meow", (<usize>::clone(&count),), mry::__fallback!(String)).output_with(self) {
                         return out;
                     }
                     (move || {
@@ -738,7 +1244,7 @@ mod test {
                 #[cfg_attr(debug_assertions, track_caller)]
                 fn meow(&self) -> String {
                     #[cfg(debug_assertions)]
-                    if let Some(out) = self.mry.record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", ()) {
+                    if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", (), mry::__fallback!(String)).output_with(self) {
                         return out;
                     }
                     (move || {
@@ -777,7 +1283,7 @@ mod test {
                 #[cfg_attr(debug_assertions, track_caller)]
                 fn meow(&self, base: String, count: usize) -> String {
                     #[cfg(debug_assertions)]
-                    if let Some(out) = self.mry.record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<String>::clone(&base), <usize>::clone(&count),)) {
+                    if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<String>::clone(&base), <usize>::clone(&count),), mry::__fallback!(String)).output_with(self) {
                         return out;
                     }
                     (move || {
@@ -816,7 +1322,7 @@ mod test {
                 #[cfg_attr(debug_assertions, track_caller)]
                 fn meow(&self, out: &'static mut String, base: &str, count: &usize) {
                     #[cfg(debug_assertions)]
-                    if let Some(out) = self.mry.record_call_and_find_mock_output::<_, ()>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<String>::clone(&out), base.to_string(), <usize>::clone(&count),)) {
+                    if let Some(out) = self.mry.record_call::<_, ()>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<String>::clone(&out), base.to_string(), <usize>::clone(&count),), mry::__fallback!(())).output_with(self) {
                         return out;
                     }
                     (move || {
@@ -856,7 +1362,7 @@ mod test {
                 #[cfg_attr(debug_assertions, track_caller)]
                 async fn meow(&self, count: usize) -> String {
                     #[cfg(debug_assertions)]
-                    if let Some(out) = self.mry.record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<usize>::clone(&count),)) {
+                    if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<usize>::clone(&count),), mry::__fallback!(String)).output_with(self) {
                         return out;
                     }
                     (move || async move {
@@ -895,7 +1401,7 @@ mod test {
                 #[cfg_attr(debug_assertions, track_caller)]
                 fn meow(&self, arg0: A, count: usize, arg2: String) -> String {
                     #[cfg(debug_assertions)]
-                    if let Some(out) = self.mry.record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<A>::clone(&arg0), <usize>::clone(&count), <String>::clone(&arg2),)) {
+                    if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<A>::clone(&arg0), <usize>::clone(&count), <String>::clone(&arg2),), mry::__fallback!(String)).output_with(self) {
                         return out;
                     }
                     let A { name } = arg0;
@@ -936,7 +1442,7 @@ mod test {
                 #[cfg_attr(debug_assertions, track_caller)]
                 pub fn meow(&self, count: usize) -> String {
                     #[cfg(debug_assertions)]
-                    if let Some(out) = self.mry.record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<usize>::clone(&count),)) {
+                    if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<usize>::clone(&count),), mry::__fallback!(String)).output_with(self) {
                         return out;
                     }
                     (move || {
@@ -964,7 +1470,7 @@ mod test {
                 #[cfg_attr(debug_assertions, track_caller)]
                 fn increment(&self, mut count: usize) -> usize {
                     #[cfg(debug_assertions)]
-                    if let Some(out) = self.mry.record_call_and_find_mock_output::<_, usize>(std::any::Any::type_id(&Self::increment), "Cat::increment", (<usize>::clone(&count),)) {
+                    if let Some(out) = self.mry.record_call::<_, usize>(std::any::Any::type_id(&Self::increment), "Cat::increment", (<usize>::clone(&count),), mry::__fallback!(usize)).output_with(self) {
                         return out;
                     }
                     (move || {
@@ -1004,7 +1510,7 @@ mod test {
                 #[cfg_attr(debug_assertions, track_caller)]
                 fn meow<'a, T: Display, const A: usize>(&self, a: usize) -> &'a String {
                     #[cfg(debug_assertions)]
-                    if let Some(out) = self.mry.record_call_and_find_mock_output::<_, &'static String>(std::any::Any::type_id(&Self::meow::<T, A>), "Cat::meow", (<usize>::clone(&a),)) {
+                    if let Some(out) = self.mry.record_call::<_, &'static String>(std::any::Any::type_id(&Self::meow::<T, A>), "Cat::meow", (<usize>::clone(&a),), mry::__fallback!(&'static String)).output_with(self) {
                         return out;
                     }
                     (move || {
@@ -1043,7 +1549,7 @@ mod test {
                 #[cfg_attr(debug_assertions, track_caller)]
                 async fn meow(&self, count: usize) -> String {
                     #[cfg(debug_assertions)]
-                    if let Some(out) = self.mry.record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<usize>::clone(&count),)) {
+                    if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<usize>::clone(&count),), mry::__fallback!(String)).output_with(self) {
                         return out;
                     }
                     (move || async move {
@@ -1083,7 +1589,7 @@ mod test {
                 #[cfg_attr(debug_assertions, track_caller)]
                 fn meow(&self, count: usize) -> impl std::future::Future<Output = String> + Send {
                     #[cfg(debug_assertions)]
-                    if let Some(out) = self.mry.record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<usize>::clone(&count),)) {
+                    if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<usize>::clone(&count),), mry::__fallback!(String)).output_with(self) {
                         return async move { out };
                     }
                     (move || {
@@ -1122,7 +1628,7 @@ mod test {
                 #[cfg_attr(debug_assertions, track_caller)]
                 fn meow(&self, count: *mut String) -> usize {
                     #[cfg(debug_assertions)]
-                    if let Some(out) = self.mry.record_call_and_find_mock_output::<_, usize>(std::any::Any::type_id(&Self::meow), "Cat::meow", (mry::send_wrapper::SendWrapper::new(<*mut String>::clone(&count)),)) {
+                    if let Some(out) = self.mry.record_call::<_, usize>(std::any::Any::type_id(&Self::meow), "Cat::meow", (mry::send_wrapper::SendWrapper::new(<*mut String>::clone(&count)),), mry::__fallback!(usize)).output_with(self) {
                         return out;
                     }
                     (move || {
@@ -1160,7 +1666,7 @@ mod test {
                 #[cfg_attr(debug_assertions, track_caller)]
                 fn meow(&self, count: usize) -> *mut String {
                     #[cfg(debug_assertions)]
-                    if let Some(out) = self.mry.record_call_and_find_mock_output::<_, mry::send_wrapper::SendWrapper<*mut String> >(std::any::Any::type_id(&Self::meow), "Cat::meow", (<usize>::clone(&count),)) {
+                    if let Some(out) = self.mry.record_call::<_, mry::send_wrapper::SendWrapper<*mut String> >(std::any::Any::type_id(&Self::meow), "Cat::meow", (<usize>::clone(&count),), mry::__fallback!(mry::send_wrapper::SendWrapper<*mut String> )).output_with(self) {
                         return mry::send_wrapper::SendWrapper::take(out);
                     }
                     (move || {
@@ -1184,6 +1690,47 @@ mod test {
         );
     }
 
+    #[test]
+    fn fallback_default_generic_output() {
+        let attr = parse_quote! {
+            mry(fallback = "default")
+        };
+        let input: ImplItemFn = parse_quote! {
+            fn meow<T: Default>(&self, count: usize) -> T {
+                T::default()
+            }
+        };
+
+        assert_eq!(
+            t_with_attr(attr, &input).to_string(),
+            quote! {
+                #[cfg_attr(debug_assertions, track_caller)]
+                fn meow<T: Default>(&self, count: usize) -> T {
+                    #[cfg(debug_assertions)]
+                    if let Some(out) = self.mry.record_call::<_, T>(std::any::Any::type_id(&Self::meow::<T>), "Cat::meow", (<usize>::clone(&count),), mry::__fallback!(T, default)).output_with(self) {
+                        return out;
+                    }
+                    (move || {
+                        T::default()
+                    })()
+                }
+
+                #[cfg(debug_assertions)]
+                #[must_use]
+                pub fn mock_meow<T: Default>(&mut self, count: impl Into<mry::ArgMatcher<usize>>) -> mry::MockLocator<(usize,), T, T, mry::Behavior1<(usize,), T> > {
+                    mry::MockLocator::new(
+                        self.mry.mocks(),
+                        std::any::Any::type_id(&Self::meow::<T>),
+                        "Cat::meow",
+                        (count.into(),).into(),
+                        std::convert::identity,
+                    )
+                }
+            }
+            .to_string()
+        );
+    }
+
     #[test]
     fn non_send_output() {
         let attr = parse_quote! {
@@ -1201,7 +1748,7 @@ mod test {
                 #[cfg_attr(debug_assertions, track_caller)]
                 fn meow(&self, count: usize) -> T {
                     #[cfg(debug_assertions)]
-                    if let Some(out) = self.mry.record_call_and_find_mock_output::<_, mry::send_wrapper::SendWrapper<T> >(std::any::Any::type_id(&Self::meow), "Cat::meow", (<usize>::clone(&count),)) {
+                    if let Some(out) = self.mry.record_call::<_, mry::send_wrapper::SendWrapper<T> >(std::any::Any::type_id(&Self::meow), "Cat::meow", (<usize>::clone(&count),), mry::__fallback!(mry::send_wrapper::SendWrapper<T> )).output_with(self) {
                         return mry::send_wrapper::SendWrapper::take(out);
                     }
                     (move || {
@@ -1242,7 +1789,7 @@ mod test {
                 #[cfg_attr(debug_assertions, track_caller)]
                 fn meow(&self, count: T) -> usize {
                     #[cfg(debug_assertions)]
-                    if let Some(out) = self.mry.record_call_and_find_mock_output::<_, usize>(std::any::Any::type_id(&Self::meow), "Cat::meow", (mry::send_wrapper::SendWrapper::new(<T>::clone(&count)),)) {
+                    if let Some(out) = self.mry.record_call::<_, usize>(std::any::Any::type_id(&Self::meow), "Cat::meow", (mry::send_wrapper::SendWrapper::new(<T>::clone(&count)),), mry::__fallback!(usize)).output_with(self) {
                         return out;
                     }
                     (move || {
@@ -1283,7 +1830,7 @@ mod test {
                 #[cfg_attr(debug_assertions, track_caller)]
                 fn meow(&self, a: A, b: B, count: usize) -> String {
                     #[cfg(debug_assertions)]
-                    if let Some(out) = self.mry.record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<usize>::clone(&count),)) {
+                    if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<usize>::clone(&count),), mry::__fallback!(String)).output_with(self) {
                         return out;
                     }
                     (move || {
@@ -1307,6 +1854,159 @@ mod test {
         );
     }
 
+    #[test]
+    fn by_ref() {
+        let attr = parse_quote! {
+            mry(by_ref(File = |file: &File| -> PathBuf { file.path().to_owned() }, Lease))
+        };
+        let input: ImplItemFn = parse_quote! {
+            fn meow(&self, file: File, lease: &Lease, count: usize) -> String {
+                "meow".repeat(count)
+            }
+        };
+
+        assert_eq!(
+            t_with_attr(attr, &input).to_string(),
+            quote! {
+                #[cfg_attr(debug_assertions, track_caller)]
+                fn meow(&self, file: File, lease: &Lease, count: usize) -> String {
+                    #[cfg(debug_assertions)]
+                    {
+                        let mut __mry_borrows = mry::Borrows::default();
+                        if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", (
+                            __mry_borrows.lend::<File, _>(&file, (|file: &File| -> PathBuf { file.path().to_owned() })(&file)),
+                            __mry_borrows.lend::<Lease, _>(&*lease, ()),
+                            <usize>::clone(&count),
+                        ), mry::__fallback!(String)).output_with(self) {
+                            return out;
+                        }
+                    }
+                    (move || {
+                        "meow".repeat(count)
+                    })()
+                }
+
+                #[cfg(debug_assertions)]
+                #[must_use]
+                pub fn mock_meow(&mut self, file: impl Into<mry::ArgMatcher<mry::ByRef<File, PathBuf> >>, lease: impl Into<mry::ArgMatcher<mry::ByRef<Lease, ()> >>, count: impl Into<mry::ArgMatcher<usize>>) -> mry::MockLocator<(mry::ByRef<File, PathBuf>, mry::ByRef<Lease, ()>, usize,), String, String, mry::Behavior3<(mry::ByRef<File, PathBuf>, mry::ByRef<Lease, ()>, usize,), String> > {
+                    mry::MockLocator::new(
+                        self.mry.mocks(),
+                        std::any::Any::type_id(&Self::meow),
+                        "Cat::meow",
+                        (file.into(), lease.into(), count.into(),).into(),
+                        std::convert::identity,
+                    )
+                }
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn by_ref_rejects_lifetimes() {
+        let attr = parse_quote! {
+            mry(by_ref(MutexGuard))
+        };
+        let input: ImplItemFn = parse_quote! {
+            fn meow(&self, guard: MutexGuard<'_, u8>) -> String {
+                "meow".to_string()
+            }
+        };
+
+        assert!(t_with_attr(attr, &input)
+            .0
+            .to_string()
+            .contains("by_ref` takes only `'static` types"));
+    }
+
+    #[test]
+    fn by_ref_fns() {
+        let attr = parse_quote! {
+            mry(by_ref_fns(meow))
+        };
+        let input: ImplItemFn = parse_quote! {
+            fn meow(&self, count: usize) -> String {
+                "meow".repeat(count)
+            }
+        };
+
+        assert_eq!(
+            t_with_attr(attr, &input).to_string(),
+            quote! {
+                #[cfg_attr(debug_assertions, track_caller)]
+                fn meow(&self, count: usize) -> String {
+                    #[cfg(debug_assertions)]
+                    {
+                        let mut __mry_borrows = mry::Borrows::default();
+                        if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", (__mry_borrows.lend::<usize, _>(&count, ()),), mry::__fallback!(String)).output_with(self) {
+                            return out;
+                        }
+                    }
+                    (move || {
+                        "meow".repeat(count)
+                    })()
+                }
+
+                #[cfg(debug_assertions)]
+                #[must_use]
+                pub fn mock_meow(&mut self, count: impl Into<mry::ArgMatcher<mry::ByRef<usize, ()> >>) -> mry::MockLocator<(mry::ByRef<usize, ()>,), String, String, mry::Behavior1<(mry::ByRef<usize, ()>,), String> > {
+                    mry::MockLocator::new(
+                        self.mry.mocks(),
+                        std::any::Any::type_id(&Self::meow),
+                        "Cat::meow",
+                        (count.into(),).into(),
+                        std::convert::identity,
+                    )
+                }
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn arg_into() {
+        let attr = parse_quote! {
+            mry(arg_into(body = Vec<u8>, path = PathBuf))
+        };
+        let input: ImplItemFn = parse_quote! {
+            fn send<P: AsRef<Path>>(&self, body: impl AsRef<[u8]>, path: &P) -> usize {
+                body.as_ref().len()
+            }
+        };
+
+        assert_eq!(
+            t_with_attr(attr, &input).to_string(),
+            quote! {
+                #[cfg_attr(debug_assertions, track_caller)]
+                fn send<P: AsRef<Path> >(&self, body: impl AsRef<[u8]>, path: &P) -> usize {
+                    #[cfg(debug_assertions)]
+                    if let Some(out) = self.mry.record_call::<_, usize>(std::any::TypeId::of::<mry::NamedKey<(), { mry::named_key_hash(concat!(module_path!(), "::", "Cat::send")) }>>(), "Cat::send", (
+                        Into::<Vec<u8> >::into(<[u8] as ToOwned>::to_owned(AsRef::<[u8]>::as_ref(&body))),
+                        Into::<PathBuf>::into(<Path as ToOwned>::to_owned(AsRef::<Path>::as_ref(path))),
+                    ), mry::__fallback!(usize)).output_with(self) {
+                        return out;
+                    }
+                    (move || {
+                        body.as_ref().len()
+                    })()
+                }
+
+                #[cfg(debug_assertions)]
+                #[must_use]
+                pub fn mock_send(&mut self, body: impl Into<mry::ArgMatcher<Vec<u8> >>, path: impl Into<mry::ArgMatcher<PathBuf>>) -> mry::MockLocator<(Vec<u8>, PathBuf,), usize, usize, mry::Behavior2<(Vec<u8>, PathBuf,), usize> > {
+                    mry::MockLocator::new(
+                        self.mry.mocks(),
+                        std::any::TypeId::of::<mry::NamedKey<(), { mry::named_key_hash(concat!(module_path!(), "::", "Cat::send")) }>>(),
+                        "Cat::send",
+                        (body.into(), path.into(),).into(),
+                        std::convert::identity,
+                    )
+                }
+            }
+            .to_string()
+        );
+    }
+
     #[test]
     fn skip_return_type_no_effect() {
         let attr = parse_quote! {
@@ -1325,7 +2025,7 @@ mod test {
                 #[cfg_attr(debug_assertions, track_caller)]
                 fn meow(&self, a: A, b: B, count: usize) -> T {
                     #[cfg(debug_assertions)]
-                    if let Some(out) = self.mry.record_call_and_find_mock_output::<_, T>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<A>::clone(&a), <B>::clone(&b), <usize>::clone(&count),)) {
+                    if let Some(out) = self.mry.record_call::<_, T>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<A>::clone(&a), <B>::clone(&b), <usize>::clone(&count),), mry::__fallback!(T)).output_with(self) {
                         return out;
                     }
                     (move || {
@@ -1365,7 +2065,7 @@ mod test {
                 #[track_caller]
                 fn meow(&self, count: usize) -> String {
                     #[cfg(debug_assertions)]
-                    if let Some(out) = self.mry.record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<usize>::clone(&count),)) {
+                    if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<usize>::clone(&count),), mry::__fallback!(String)).output_with(self) {
                         return out;
                     }
                     "meow".repeat(count)
@@ -1403,7 +2103,7 @@ mod test {
                 #[cfg_attr(debug_assertions, track_caller)]
                 fn meow(&self, count: usize) -> String {
                     #[cfg(debug_assertions)]
-                    if let Some(out) = self.mry.record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<usize>::clone(&count),)) {
+                    if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<usize>::clone(&count),), mry::__fallback!(String)).output_with(self) {
                         return out;
                     }
                     "meow".repeat(count)
@@ -1446,7 +2146,7 @@ mod test {
                     T: Display + Clone,
                 {
                     #[cfg(debug_assertions)]
-                    if let Some(out) = self.mry.record_call_and_find_mock_output::<_, String>(std::any::Any::type_id(&Self::meow::<T>), "Cat::meow", (<T>::clone(&value),)) {
+                    if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&Self::meow::<T>), "Cat::meow", (<T>::clone(&value),), mry::__fallback!(String)).output_with(self) {
                         return out;
                     }
                     (move || {
@@ -1472,4 +2172,103 @@ mod test {
             .to_string()
         );
     }
+
+    #[test]
+    fn supports_unsafe_fn() {
+        let input: ImplItemFn = parse2(quote! {
+            unsafe fn meow(&self, count: usize) -> String {
+                "meow".repeat(count)
+            }
+        })
+        .unwrap();
+
+        assert_eq!(
+            t(&input).to_string(),
+            quote! {
+                #[cfg_attr(debug_assertions, track_caller)]
+                unsafe fn meow(&self, count: usize) -> String {
+                    #[cfg(debug_assertions)]
+                    if let Some(out) = self.mry.record_call::<_, String>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<usize>::clone(&count),), mry::__fallback!(String)).output_with(self) {
+                        return out;
+                    }
+                    (move || {
+                        "meow".repeat(count)
+                    })()
+                }
+
+                #[cfg(debug_assertions)]
+                #[must_use]
+                pub fn mock_meow(&mut self, count: impl Into<mry::ArgMatcher<usize>>) -> mry::MockLocator<(usize,), String, String, mry::Behavior1<(usize,), String> > {
+                    mry::MockLocator::new(
+                        self.mry.mocks(),
+                        std::any::Any::type_id(&Self::meow),
+                        "Cat::meow",
+                        (count.into(),).into(),
+                        std::convert::identity,
+                    )
+                }
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn extern_fn_has_no_track_caller() {
+        let input: ImplItemFn = parse2(quote! {
+            extern "C" fn meow(&self, count: usize) -> usize {
+                count
+            }
+        })
+        .unwrap();
+
+        assert_eq!(
+            t(&input).0.to_string(),
+            quote! {
+                extern "C" fn meow(&self, count: usize) -> usize {
+                    #[cfg(debug_assertions)]
+                    if let Some(out) = self.mry.record_call::<_, usize>(std::any::Any::type_id(&Self::meow), "Cat::meow", (<usize>::clone(&count),), mry::__fallback!(usize)).output_with(self) {
+                        return out;
+                    }
+                    count
+                }
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn explicit_rust_abi_has_track_caller() {
+        let input: ImplItemFn = parse2(quote! {
+            extern "Rust" fn meow(&self) -> usize {
+                1
+            }
+        })
+        .unwrap();
+
+        assert!(t(&input)
+            .0
+            .to_string()
+            .starts_with(&quote!(#[cfg_attr(debug_assertions, track_caller)]).to_string()));
+    }
+
+    #[test]
+    fn const_fn_is_compile_error() {
+        let input: ImplItemFn = parse2(quote! {
+            const fn meow(&self, count: usize) -> usize {
+                count
+            }
+        })
+        .unwrap();
+
+        assert_eq!(
+            t(&input).to_string(),
+            quote! {
+                ::core::compile_error! { "mry cannot mock `const fn`. Remove `const` or skip it with `#[mry::mry(skip_fns(meow))]`" }
+                const fn meow(&self, count: usize) -> usize {
+                    count
+                }
+            }
+            .to_string()
+        );
+    }
 }
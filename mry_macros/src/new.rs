@@ -1,8 +1,44 @@
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{ExprStruct, Member};
+use syn::parse::{Parse, ParseStream};
+use syn::{Expr, ExprStruct, Member, Token};
 
-pub(crate) fn transform(input: ExprStruct) -> TokenStream {
+/// Input of `mry::new!`: `Cat { .. }` or `"name", Cat { .. }`
+pub(crate) struct New {
+    pub name: Option<Expr>,
+    pub input: ExprStruct,
+}
+
+impl Parse for New {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let fork = input.fork();
+        if fork.parse::<ExprStruct>().is_ok() && fork.is_empty() {
+            return Ok(Self {
+                name: None,
+                input: input.parse()?,
+            });
+        }
+        let name = input.parse()?;
+        input.parse::<Token![,]>()?;
+        Ok(Self {
+            name: Some(name),
+            input: input.parse()?,
+        })
+    }
+}
+
+pub(crate) fn transform(New { name, input }: New) -> TokenStream {
+    match name {
+        Some(name) => transform_with_mry(input, quote![mry::Mry::named(#name)]),
+        None => transform_with_mry(input, quote![Default::default()]),
+    }
+}
+
+pub(crate) fn transform_spy(input: ExprStruct) -> TokenStream {
+    transform_with_mry(input, quote![mry::Mry::spy()])
+}
+
+fn transform_with_mry(input: ExprStruct, mry: TokenStream) -> TokenStream {
     let ident = input.path.clone();
     let mut fields: Vec<_> = input
         .fields
@@ -19,7 +55,7 @@ pub(crate) fn transform(input: ExprStruct) -> TokenStream {
         })
         .collect();
     fields.push(quote! {
-        mry: Default::default(),
+        mry: #mry,
     });
     quote! {
         #ident {
@@ -37,7 +73,7 @@ mod test {
 
     #[test]
     fn adds_mry() {
-        let input: ExprStruct = parse2(quote! {
+        let input: New = parse2(quote! {
             Cat {
                 name: "aaa",
             }
@@ -57,8 +93,29 @@ mod test {
     }
 
     #[test]
-    fn support_generics() {
+    fn spy() {
         let input: ExprStruct = parse2(quote! {
+            Cat {
+                name: "aaa",
+            }
+        })
+        .unwrap();
+
+        assert_eq!(
+            transform_spy(input).to_string(),
+            quote! {
+                Cat {
+                    name: "aaa",
+                    mry: mry::Mry::spy(),
+                }
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn support_generics() {
+        let input: New = parse2(quote! {
             Cat::<A> {
                 name: "aaa",
             }
@@ -76,4 +133,25 @@ mod test {
             .to_string()
         );
     }
+
+    #[test]
+    fn named() {
+        let input: New = parse2(quote! {
+            "primary", Cat {
+                name: "aaa",
+            }
+        })
+        .unwrap();
+
+        assert_eq!(
+            transform(input).to_string(),
+            quote! {
+                Cat {
+                    name: "aaa",
+                    mry: mry::Mry::named("primary"),
+                }
+            }
+            .to_string()
+        );
+    }
 }
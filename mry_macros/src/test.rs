@@ -0,0 +1,317 @@
+use std::collections::HashSet;
+
+use darling::FromMeta;
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn::punctuated::Punctuated;
+use syn::visit::{self, Visit};
+use syn::{
+    parse_quote, Expr, ExprCall, ExprPath, Ident, ItemFn, Macro, Path, Token, Type, TypePath,
+};
+
+use crate::attrs::Skip;
+use crate::lock::{self, LockPaths};
+
+/// Arguments of `#[mry::test]` like `skip(mock_server)`
+#[derive(FromMeta, Default)]
+pub(crate) struct TestAttr {
+    /// Functions named `mock_*` that are not generated by `#[mry::mry]`, like test helpers
+    pub skip: Option<Skip>,
+}
+
+/// Collects the functions of static `mock_*` calls like `mock_hello(..)` or `Cat::mock_meow(..)`.
+#[derive(Default)]
+struct StaticMockCalls<'a> {
+    skip: &'a [Path],
+    paths: Vec<Type>,
+    seen: HashSet<String>,
+}
+
+impl StaticMockCalls<'_> {
+    /// Whether the call is of a function in `skip(..)`, like `mock_server` or `helpers::mock_server`
+    fn is_skipped(&self, path: &ExprPath) -> bool {
+        let segments: Vec<_> = path.path.segments.iter().map(|s| &s.ident).collect();
+        self.skip.iter().any(|skip| {
+            let skip: Vec<_> = skip.segments.iter().map(|s| &s.ident).collect();
+            segments.ends_with(&skip)
+        })
+    }
+}
+
+impl Visit<'_> for StaticMockCalls<'_> {
+    fn visit_expr_call(&mut self, call: &ExprCall) {
+        if let Expr::Path(path) = &*call.func {
+            if let Some(ty) = mocked_function(path).filter(|_| !self.is_skipped(path)) {
+                if self.seen.insert(ty.to_token_stream().to_string()) {
+                    self.paths.push(ty);
+                }
+            }
+        }
+        visit::visit_expr_call(self, call);
+    }
+
+    fn visit_macro(&mut self, mac: &Macro) {
+        // Most macros in tests like `assert_eq!` take comma separated expressions.
+        if let Ok(exprs) = mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) {
+            for expr in exprs {
+                self.visit_expr(&expr);
+            }
+        }
+    }
+}
+
+/// Returns the function mocked by a call like `mock_hello(..)` or `Cat::mock_meow(..)`.
+/// Functions of mry itself like `mry::mock_fn(..)` are not mocks of `#[mry::mry]`.
+fn mocked_function(path: &ExprPath) -> Option<Type> {
+    let segments = &path.path.segments;
+    if path.qself.is_none() && segments.first()?.ident == "mry" {
+        return None;
+    }
+    let last = segments.last()?;
+    if last.ident == "mock_fn" {
+        return None;
+    }
+    // `mock_*` functions generated by `#[mry::mry]` are named after an identifier
+    let name = last.ident.to_string();
+    let ident: Ident = syn::parse_str(name.strip_prefix("mock_")?).ok()?;
+    let mut path = path.clone();
+    path.path.segments.last_mut()?.ident = Ident::new(&ident.to_string(), last.ident.span());
+    Some(Type::Path(TypePath {
+        qself: path.qself,
+        path: path.path,
+    }))
+}
+
+/// Test attributes that make `#[mry::test]` not add `#[test]`
+const TEST_ATTRS: &[&str] = &[
+    "test",
+    "tokio :: test",
+    "async_std :: test",
+    "actix_rt :: test",
+    "actix_web :: test",
+    "futures_test :: test",
+    "test_log :: test",
+];
+
+fn has_test_attr(input: &ItemFn) -> bool {
+    input.attrs.iter().any(|attr| {
+        let path = attr.path().to_token_stream().to_string();
+        TEST_ATTRS.contains(&path.trim_start_matches(":: "))
+    })
+}
+
+pub(crate) fn transform(test_attr: &TestAttr, mut input: ItemFn) -> TokenStream {
+    if !has_test_attr(&input) {
+        input.attrs.insert(0, parse_quote!(#[test]));
+    }
+    let mut calls = StaticMockCalls {
+        skip: test_attr.skip.as_ref().map_or(&[], |skip| &skip.0),
+        ..Default::default()
+    };
+    calls.visit_block(&input.block);
+    if calls.paths.is_empty() {
+        return input.into_token_stream();
+    }
+    lock::transform(LockPaths(calls.paths), input)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use quote::quote;
+    use syn::parse2;
+
+    use super::*;
+
+    #[test]
+    fn locks_static_mocks() {
+        let input: ItemFn = parse2(quote! {
+            fn test_meow() {
+                mock_hello(Any).returns("a".into());
+                a::Cat::mock_meow(1).returns("b".into());
+                <MockCat as Cat>::mock_new(Any).returns(MockCat::default());
+                cat.mock_meow(1).returns("c".into());
+                assert_eq!(hello(1), "a");
+            }
+        })
+        .unwrap();
+
+        assert_eq!(
+            transform(&TestAttr::default(), input).to_string(),
+            quote! {
+                #[test]
+                fn test_meow() {
+                    mry::__lock_and_run(mry::__mutexes(vec![
+                        (std::any::Any::type_id(&hello), "hello".to_string()),
+                        (std::any::Any::type_id(&a::Cat::meow), "a::Cat::meow".to_string()),
+                        (std::any::Any::type_id(&<MockCat as Cat>::new), "<MockCat as Cat>::new".to_string()),
+                    ]), move | | {
+                        mock_hello(Any).returns("a".into());
+                        a::Cat::mock_meow(1).returns("b".into());
+                        <MockCat as Cat>::mock_new(Any).returns(MockCat::default());
+                        cat.mock_meow(1).returns("c".into());
+                        assert_eq!(hello(1), "a");
+                    })
+                }
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn deduplicates_and_finds_in_macros() {
+        let input: ItemFn = parse2(quote! {
+            fn test_meow() {
+                mock_hello(1).returns("a".into());
+                assert_eq!(mock_hello(2).returns("b".into()).is_some(), true);
+            }
+        })
+        .unwrap();
+
+        assert_eq!(
+            transform(&TestAttr::default(), input).to_string(),
+            quote! {
+                #[test]
+                fn test_meow() {
+                    mry::__lock_and_run(mry::__mutexes(vec![
+                        (std::any::Any::type_id(&hello), "hello".to_string()),
+                    ]), move | | {
+                        mock_hello(1).returns("a".into());
+                        assert_eq!(mock_hello(2).returns("b".into()).is_some(), true);
+                    })
+                }
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn keeps_other_test_attribute() {
+        let input: ItemFn = parse2(quote! {
+            #[tokio::test]
+            async fn test_meow() {
+                mock_hello(Any).returns("a".into());
+            }
+        })
+        .unwrap();
+
+        assert_eq!(
+            transform(&TestAttr::default(), input).to_string(),
+            quote! {
+                #[tokio::test]
+                async fn test_meow() {
+                    mry::__async_lock_and_run(mry::__mutexes(vec![
+                        (std::any::Any::type_id(&hello), "hello".to_string()),
+                    ]), move | | Box::pin(async {
+                        mock_hello(Any).returns("a".into());
+                    })).await
+                }
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn ignores_functions_of_mry() {
+        let input: ItemFn = parse2(quote! {
+            fn test_meow() {
+                let (handler, handle) = mry::mock_fn::<(u8,), u8>();
+                let (handler, handle) = mock_fn::<(u8,), u8>();
+                mry::mock_other();
+                mock_();
+            }
+        })
+        .unwrap();
+
+        assert_eq!(
+            transform(&TestAttr::default(), input).to_string(),
+            quote! {
+                #[test]
+                fn test_meow() {
+                    let (handler, handle) = mry::mock_fn::<(u8,), u8>();
+                    let (handler, handle) = mock_fn::<(u8,), u8>();
+                    mry::mock_other();
+                    mock_();
+                }
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn adds_test_attribute_unless_known() {
+        let input: ItemFn = parse2(quote! {
+            #[my_macros::test]
+            fn test_meow() {}
+        })
+        .unwrap();
+
+        assert_eq!(
+            transform(&TestAttr::default(), input).to_string(),
+            quote! {
+                #[test]
+                #[my_macros::test]
+                fn test_meow() {}
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn no_static_mocks() {
+        let input: ItemFn = parse2(quote! {
+            fn test_meow() {
+                cat.mock_meow(1).returns("c".into());
+            }
+        })
+        .unwrap();
+
+        assert_eq!(
+            transform(&TestAttr::default(), input).to_string(),
+            quote! {
+                #[test]
+                fn test_meow() {
+                    cat.mock_meow(1).returns("c".into());
+                }
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn skips_listed_functions() {
+        let attr = TestAttr::from_list(
+            &darling::ast::NestedMeta::parse_meta_list(quote!(skip(mock_server, db::mock_db)))
+                .unwrap(),
+        )
+        .unwrap();
+        let input: ItemFn = parse2(quote! {
+            fn test_meow() {
+                let server = mock_server();
+                let db = helpers::db::mock_db();
+                let other = other::mock_db();
+                mock_hello(Any).returns("a".into());
+            }
+        })
+        .unwrap();
+
+        assert_eq!(
+            transform(&attr, input).to_string(),
+            quote! {
+                #[test]
+                fn test_meow() {
+                    mry::__lock_and_run(mry::__mutexes(vec![
+                        (std::any::Any::type_id(&other::db), "other::db".to_string()),
+                        (std::any::Any::type_id(&hello), "hello".to_string()),
+                    ]), move | | {
+                        let server = mock_server();
+                        let db = helpers::db::mock_db();
+                        let other = other::mock_db();
+                        mock_hello(Any).returns("a".into());
+                    })
+                }
+            }
+            .to_string()
+        );
+    }
+}